@@ -1,5 +1,116 @@
 use crate::config::{Config, ConfigColumnAlign, ConfigSortOrder};
 use crate::process::ProcessInfo;
+use std::fmt;
+
+/// Typed raw value of a column
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+pub enum ColumnValue {
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    Bytes(u64),
+    /// Duration in seconds
+    Duration(u64),
+    Str(String),
+    List(Vec<ColumnValue>),
+}
+
+impl ColumnValue {
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            ColumnValue::Int(x) => serde_json::json!(x),
+            ColumnValue::UInt(x) => serde_json::json!(x),
+            ColumnValue::Float(x) => serde_json::json!(x),
+            ColumnValue::Bytes(x) => serde_json::json!(x),
+            ColumnValue::Duration(x) => serde_json::json!(x),
+            ColumnValue::Str(x) => serde_json::json!(x),
+            ColumnValue::List(x) => x.iter().map(|x| x.to_json()).collect(),
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            ColumnValue::Int(x) => Some(*x as f64),
+            ColumnValue::UInt(x) => Some(*x as f64),
+            ColumnValue::Float(x) => Some(*x),
+            ColumnValue::Bytes(x) => Some(*x as f64),
+            ColumnValue::Duration(x) => Some(*x as f64),
+            ColumnValue::Str(_) | ColumnValue::List(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for ColumnValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ColumnValue::Int(x) => write!(f, "{}", x),
+            ColumnValue::UInt(x) => write!(f, "{}", x),
+            ColumnValue::Float(x) => write!(f, "{}", x),
+            ColumnValue::Bytes(x) => write!(f, "{}", x),
+            ColumnValue::Duration(x) => write!(f, "{}", x),
+            ColumnValue::Str(x) => write!(f, "{}", x),
+            ColumnValue::List(x) => {
+                let x: Vec<_> = x.iter().map(|x| x.to_string()).collect();
+                write!(f, "{}", x.join(","))
+            }
+        }
+    }
+}
+
+impl From<&i32> for ColumnValue {
+    fn from(x: &i32) -> Self {
+        ColumnValue::Int(i64::from(*x))
+    }
+}
+
+impl From<&i64> for ColumnValue {
+    fn from(x: &i64) -> Self {
+        ColumnValue::Int(*x)
+    }
+}
+
+impl From<&u32> for ColumnValue {
+    fn from(x: &u32) -> Self {
+        ColumnValue::Int(i64::from(*x))
+    }
+}
+
+impl From<&u64> for ColumnValue {
+    fn from(x: &u64) -> Self {
+        ColumnValue::UInt(*x)
+    }
+}
+
+impl From<&String> for ColumnValue {
+    fn from(x: &String) -> Self {
+        ColumnValue::Str(x.clone())
+    }
+}
+
+/// Type name of `ColumnValue` converted from the raw content by `From`
+pub trait ValueType {
+    const VALUE_TYPE: &'static str;
+}
+
+impl ValueType for i32 {
+    const VALUE_TYPE: &'static str = "Int";
+}
+
+impl ValueType for i64 {
+    const VALUE_TYPE: &'static str = "Int";
+}
+
+impl ValueType for u32 {
+    const VALUE_TYPE: &'static str = "Int";
+}
+
+impl ValueType for u64 {
+    const VALUE_TYPE: &'static str = "UInt";
+}
+
+impl ValueType for String {
+    const VALUE_TYPE: &'static str = "Str";
+}
 
 pub trait Column {
     fn add(&mut self, proc: &ProcessInfo);
@@ -20,8 +131,12 @@ pub trait Column {
     ) -> String;
     fn display_unit(&self, align: &ConfigColumnAlign) -> String;
     fn display_content(&self, pid: i32, align: &ConfigColumnAlign) -> Option<String>;
+    fn value(&self, pid: i32) -> Option<ColumnValue>;
+    /// Name of the `ColumnValue` variant returned by `value`, or `None` if the column has no value
+    fn value_type(&self) -> Option<&'static str>;
     fn find_partial(&self, pid: i32, keyword: &str, ignore_case: bool) -> bool;
     fn find_exact(&self, pid: i32, keyword: &str) -> bool;
+    fn find_regex(&self, pid: i32, keyword: &regex::Regex) -> bool;
     fn sorted_pid(&self, order: &ConfigSortOrder) -> Vec<i32>;
     fn apply_visible(&mut self, visible_pids: &[i32]);
     fn reset_width(
@@ -87,6 +202,75 @@ macro_rules! column_default_display_content {
     };
 }
 
+#[macro_export]
+macro_rules! column_default_value {
+    ($x:ty) => {
+        fn value(&self, pid: i32) -> Option<crate::column::ColumnValue> {
+            self.raw_contents
+                .get(&pid)
+                .map(crate::column::ColumnValue::from)
+        }
+
+        fn value_type(&self) -> Option<&'static str> {
+            Some(<$x as crate::column::ValueType>::VALUE_TYPE)
+        }
+    };
+    ($x:ty, $v:ident) => {
+        fn value(&self, pid: i32) -> Option<crate::column::ColumnValue> {
+            self.raw_contents
+                .get(&pid)
+                .map(|x| crate::column::ColumnValue::$v(*x))
+        }
+
+        fn value_type(&self) -> Option<&'static str> {
+            Some(stringify!($v))
+        }
+    };
+    ($x:ty, $v:ident, $f:expr) => {
+        fn value(&self, pid: i32) -> Option<crate::column::ColumnValue> {
+            self.raw_contents.get(&pid).map($f)
+        }
+
+        fn value_type(&self) -> Option<&'static str> {
+            Some(stringify!($v))
+        }
+    };
+}
+
+/// `value`, `value_type`, `find_exact` and `find_regex` for columns whose raw content is a list like `Vec<u32>`
+#[macro_export]
+macro_rules! column_default_list {
+    () => {
+        fn value(&self, pid: i32) -> Option<crate::column::ColumnValue> {
+            self.raw_contents.get(&pid).map(|x| {
+                crate::column::ColumnValue::List(
+                    x.iter().map(crate::column::ColumnValue::from).collect(),
+                )
+            })
+        }
+
+        fn value_type(&self) -> Option<&'static str> {
+            Some("List")
+        }
+
+        fn find_exact(&self, pid: i32, keyword: &str) -> bool {
+            if let Some(content) = self.raw_contents.get(&pid) {
+                content.iter().any(|x| x.to_string() == keyword)
+            } else {
+                false
+            }
+        }
+
+        fn find_regex(&self, pid: i32, keyword: &regex::Regex) -> bool {
+            if let Some(content) = self.raw_contents.get(&pid) {
+                content.iter().any(|x| keyword.is_match(&x.to_string()))
+            } else {
+                false
+            }
+        }
+    };
+}
+
 #[macro_export]
 macro_rules! column_default_find_partial {
     () => {
@@ -117,6 +301,19 @@ macro_rules! column_default_find_exact {
     };
 }
 
+#[macro_export]
+macro_rules! column_default_find_regex {
+    () => {
+        fn find_regex(&self, pid: i32, keyword: &regex::Regex) -> bool {
+            if let Some(content) = self.fmt_contents.get(&pid) {
+                keyword.is_match(content)
+            } else {
+                false
+            }
+        }
+    };
+}
+
 #[macro_export]
 macro_rules! column_default_sorted_pid {
     ($x:ty) => {
@@ -205,8 +402,38 @@ macro_rules! column_default {
         crate::column_default_display_header!();
         crate::column_default_display_unit!();
         crate::column_default_display_content!();
+        crate::column_default_value!($x);
+        crate::column_default_find_partial!();
+        crate::column_default_find_exact!();
+        crate::column_default_find_regex!();
+        crate::column_default_sorted_pid!($x);
+        crate::column_default_apply_visible!();
+        crate::column_default_reset_width!();
+        crate::column_default_update_width!();
+        crate::column_default_get_width!();
+    };
+    ($x:ty, $v:ident) => {
+        crate::column_default_display_header!();
+        crate::column_default_display_unit!();
+        crate::column_default_display_content!();
+        crate::column_default_value!($x, $v);
+        crate::column_default_find_partial!();
+        crate::column_default_find_exact!();
+        crate::column_default_find_regex!();
+        crate::column_default_sorted_pid!($x);
+        crate::column_default_apply_visible!();
+        crate::column_default_reset_width!();
+        crate::column_default_update_width!();
+        crate::column_default_get_width!();
+    };
+    ($x:ty, $v:ident, $f:expr) => {
+        crate::column_default_display_header!();
+        crate::column_default_display_unit!();
+        crate::column_default_display_content!();
+        crate::column_default_value!($x, $v, $f);
         crate::column_default_find_partial!();
         crate::column_default_find_exact!();
+        crate::column_default_find_regex!();
         crate::column_default_sorted_pid!($x);
         crate::column_default_apply_visible!();
         crate::column_default_reset_width!();
@@ -214,3 +441,28 @@ macro_rules! column_default {
         crate::column_default_get_width!();
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_json() {
+        assert_eq!(ColumnValue::Int(-1).to_json(), serde_json::json!(-1));
+        assert_eq!(
+            ColumnValue::UInt(u64::MAX).to_json(),
+            serde_json::json!(u64::MAX)
+        );
+        assert_eq!(ColumnValue::Float(1.5).to_json(), serde_json::json!(1.5));
+        assert_eq!(ColumnValue::Bytes(1024).to_json(), serde_json::json!(1024));
+        assert_eq!(ColumnValue::Duration(60).to_json(), serde_json::json!(60));
+        assert_eq!(
+            ColumnValue::Str(String::from("a \"b\"")).to_json(),
+            serde_json::json!("a \"b\"")
+        );
+        assert_eq!(
+            ColumnValue::List(vec![ColumnValue::UInt(80), ColumnValue::UInt(443)]).to_json(),
+            serde_json::json!([80, 443])
+        );
+    }
+}
@@ -38,7 +38,7 @@ impl Column for CpuTime {
         self.raw_contents.insert(proc.pid, raw_content);
     }
 
-    column_default!(u64);
+    column_default!(u64, Duration);
 }
 
 #[cfg_attr(tarpaulin, skip)]
@@ -56,7 +56,7 @@ impl Column for CpuTime {
         self.raw_contents.insert(proc.pid, raw_content);
     }
 
-    column_default!(u64);
+    column_default!(u64, Duration);
 }
 
 #[cfg_attr(tarpaulin, skip)]
@@ -72,5 +72,5 @@ impl Column for CpuTime {
         self.raw_contents.insert(proc.pid, raw_content);
     }
 
-    column_default!(u64);
+    column_default!(u64, Duration);
 }
@@ -78,7 +78,7 @@ impl Column for ElapsedTime {
         self.raw_contents.insert(proc.pid, raw_content);
     }
 
-    column_default!(Duration);
+    column_default!(Duration, Duration, |x| crate::column::ColumnValue::Duration(x.num_seconds().max(0) as u64));
 }
 
 #[cfg_attr(tarpaulin, skip)]
@@ -93,7 +93,7 @@ impl Column for ElapsedTime {
         self.raw_contents.insert(proc.pid, raw_content);
     }
 
-    column_default!(Duration);
+    column_default!(Duration, Duration, |x| crate::column::ColumnValue::Duration(x.num_seconds().max(0) as u64));
 }
 
 #[cfg_attr(tarpaulin, skip)]
@@ -107,5 +107,5 @@ impl Column for ElapsedTime {
         self.raw_contents.insert(proc.pid, raw_content);
     }
 
-    column_default!(Duration);
+    column_default!(Duration, Duration, |x| crate::column::ColumnValue::Duration(x.num_seconds().max(0) as u64));
 }
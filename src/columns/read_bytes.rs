@@ -44,7 +44,7 @@ impl Column for ReadBytes {
         self.raw_contents.insert(proc.pid, raw_content);
     }
 
-    column_default!(u64);
+    column_default!(u64, Bytes);
 }
 
 #[cfg_attr(tarpaulin, skip)]
@@ -66,7 +66,7 @@ impl Column for ReadBytes {
         self.raw_contents.insert(proc.pid, raw_content);
     }
 
-    column_default!(u64);
+    column_default!(u64, Bytes);
 }
 
 #[cfg_attr(tarpaulin, skip)]
@@ -83,5 +83,5 @@ impl Column for ReadBytes {
         self.raw_contents.insert(proc.pid, raw_content);
     }
 
-    column_default!(u64);
+    column_default!(u64, Bytes);
 }
@@ -54,7 +54,7 @@ impl Column for StartTime {
         self.raw_contents.insert(proc.pid, raw_content);
     }
 
-    column_default!(DateTime<Local>);
+    column_default!(DateTime<Local>, Int, |x| crate::column::ColumnValue::Int(x.timestamp()));
 }
 
 #[cfg_attr(tarpaulin, skip)]
@@ -69,7 +69,7 @@ impl Column for StartTime {
         self.raw_contents.insert(proc.pid, raw_content);
     }
 
-    column_default!(DateTime<Local>);
+    column_default!(DateTime<Local>, Int, |x| crate::column::ColumnValue::Int(x.timestamp()));
 }
 
 #[cfg_attr(tarpaulin, skip)]
@@ -83,5 +83,5 @@ impl Column for StartTime {
         self.raw_contents.insert(proc.pid, raw_content);
     }
 
-    column_default!(DateTime<Local>);
+    column_default!(DateTime<Local>, Int, |x| crate::column::ColumnValue::Int(x.timestamp()));
 }
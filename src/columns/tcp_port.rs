@@ -13,7 +13,7 @@ pub struct TcpPort {
     header: String,
     unit: String,
     fmt_contents: HashMap<i32, String>,
-    raw_contents: HashMap<i32, String>,
+    raw_contents: HashMap<i32, Vec<u32>>,
     width: usize,
     #[cfg(target_os = "linux")]
     tcp_entry: Vec<TcpNetEntry>,
@@ -42,7 +42,7 @@ impl TcpPort {
 #[cfg(target_os = "linux")]
 impl Column for TcpPort {
     fn add(&mut self, proc: &ProcessInfo) {
-        let fmt_content = if let Ok(fds) = proc.curr_proc.fd() {
+        let (fmt_content, raw_content) = if let Ok(fds) = proc.curr_proc.fd() {
             let mut socks = Vec::new();
             for fd in fds {
                 if let FDTarget::Socket(x) = fd.target {
@@ -56,43 +56,28 @@ impl Column for TcpPort {
                 let entry = tcp_iter.find(|&x| x.inode == *sock);
                 if let Some(entry) = entry {
                     if entry.state == TcpState::Listen {
-                        ports.push(entry.local_address.port());
+                        ports.push(u32::from(entry.local_address.port()));
                     }
                 }
             }
             ports.sort();
             ports.dedup();
 
-            format!("{:?}", ports)
+            (format!("{:?}", ports), ports)
         } else {
-            String::from("")
+            (String::from(""), Vec::new())
         };
-        let raw_content = fmt_content.clone();
 
         self.fmt_contents.insert(proc.pid, fmt_content);
         self.raw_contents.insert(proc.pid, raw_content);
     }
 
-    fn find_exact(&self, pid: i32, keyword: &str) -> bool {
-        if let Some(content) = self.fmt_contents.get(&pid) {
-            let content = content.replace("[", "").replace("]", "");
-            let content = content.split(',');
-            for c in content {
-                if c == keyword {
-                    return true;
-                }
-            }
-            false
-        } else {
-            false
-        }
-    }
-
+    crate::column_default_list!();
     crate::column_default_display_header!();
     crate::column_default_display_unit!();
     crate::column_default_display_content!();
     crate::column_default_find_partial!();
-    crate::column_default_sorted_pid!(String);
+    crate::column_default_sorted_pid!(Vec<u32>);
     crate::column_default_apply_visible!();
     crate::column_default_reset_width!();
     crate::column_default_update_width!();
@@ -117,32 +102,18 @@ impl Column for TcpPort {
         ports.dedup();
 
         let fmt_content = format!("{:?}", ports);
-        let raw_content = fmt_content.clone();
+        let raw_content = ports;
 
         self.fmt_contents.insert(proc.pid, fmt_content);
         self.raw_contents.insert(proc.pid, raw_content);
     }
 
-    fn find_exact(&self, pid: i32, keyword: &str) -> bool {
-        if let Some(content) = self.fmt_contents.get(&pid) {
-            let content = content.replace("[", "").replace("]", "");
-            let content = content.split(',');
-            for c in content {
-                if c == keyword {
-                    return true;
-                }
-            }
-            false
-        } else {
-            false
-        }
-    }
-
+    crate::column_default_list!();
     crate::column_default_display_header!();
     crate::column_default_display_unit!();
     crate::column_default_display_content!();
     crate::column_default_find_partial!();
-    crate::column_default_sorted_pid!(String);
+    crate::column_default_sorted_pid!(Vec<u32>);
     crate::column_default_apply_visible!();
     crate::column_default_reset_width!();
     crate::column_default_update_width!();
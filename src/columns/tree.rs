@@ -118,6 +118,14 @@ impl Column for Tree {
         }
     }
 
+    fn value(&self, _pid: i32) -> Option<crate::column::ColumnValue> {
+        None
+    }
+
+    fn value_type(&self) -> Option<&'static str> {
+        None
+    }
+
     fn find_partial(&self, _pid: i32, _keyword: &str, _ignore_case: bool) -> bool {
         false
     }
@@ -126,6 +134,10 @@ impl Column for Tree {
         false
     }
 
+    fn find_regex(&self, _pid: i32, _keyword: &regex::Regex) -> bool {
+        false
+    }
+
     fn sorted_pid(&self, _order: &crate::config::ConfigSortOrder) -> Vec<i32> {
         let mut root_pids = Vec::new();
         for p in self.rev_tree.values() {
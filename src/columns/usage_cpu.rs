@@ -42,7 +42,7 @@ impl Column for UsageCpu {
         self.raw_contents.insert(proc.pid, raw_content);
     }
 
-    column_default!(u32);
+    column_default!(u32, Float, |x| crate::column::ColumnValue::Float(*x as f64 / 1000.0));
 }
 
 #[cfg_attr(tarpaulin, skip)]
@@ -64,7 +64,7 @@ impl Column for UsageCpu {
         self.raw_contents.insert(proc.pid, raw_content);
     }
 
-    column_default!(u32);
+    column_default!(u32, Float, |x| crate::column::ColumnValue::Float(*x as f64 / 1000.0));
 }
 
 #[cfg_attr(tarpaulin, skip)]
@@ -85,5 +85,5 @@ impl Column for UsageCpu {
         self.raw_contents.insert(proc.pid, raw_content);
     }
 
-    column_default!(u32);
+    column_default!(u32, Float, |x| crate::column::ColumnValue::Float(*x as f64 / 1000.0));
 }
@@ -88,7 +88,7 @@ impl Column for UsageMem {
         self.raw_contents.insert(proc.pid, raw_content);
     }
 
-    column_default!(u32);
+    column_default!(u32, Float, |x| crate::column::ColumnValue::Float(*x as f64 / 1000.0));
 }
 
 #[cfg_attr(tarpaulin, skip)]
@@ -103,7 +103,7 @@ impl Column for UsageMem {
         self.raw_contents.insert(proc.pid, raw_content);
     }
 
-    column_default!(u32);
+    column_default!(u32, Float, |x| crate::column::ColumnValue::Float(*x as f64 / 1000.0));
 }
 
 #[cfg_attr(tarpaulin, skip)]
@@ -118,5 +118,5 @@ impl Column for UsageMem {
         self.raw_contents.insert(proc.pid, raw_content);
     }
 
-    column_default!(u32);
+    column_default!(u32, Float, |x| crate::column::ColumnValue::Float(*x as f64 / 1000.0));
 }
@@ -44,7 +44,7 @@ impl Column for VmPeak {
         self.raw_contents.insert(proc.pid, raw_content);
     }
 
-    column_default!(u64);
+    column_default!(u64, Bytes);
 }
 
 #[cfg_attr(tarpaulin, skip)]
@@ -58,5 +58,5 @@ impl Column for VmPeak {
         self.raw_contents.insert(proc.pid, raw_content);
     }
 
-    column_default!(u64);
+    column_default!(u64, Bytes);
 }
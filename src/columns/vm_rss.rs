@@ -36,7 +36,7 @@ impl Column for VmRss {
         self.raw_contents.insert(proc.pid, raw_content);
     }
 
-    column_default!(u64);
+    column_default!(u64, Bytes);
 }
 
 #[cfg_attr(tarpaulin, skip)]
@@ -50,7 +50,7 @@ impl Column for VmRss {
         self.raw_contents.insert(proc.pid, raw_content);
     }
 
-    column_default!(u64);
+    column_default!(u64, Bytes);
 }
 
 #[cfg_attr(tarpaulin, skip)]
@@ -64,5 +64,5 @@ impl Column for VmRss {
         self.raw_contents.insert(proc.pid, raw_content);
     }
 
-    column_default!(u64);
+    column_default!(u64, Bytes);
 }
@@ -78,6 +78,13 @@ fn default_color_by_theme() -> ConfigColorByTheme {
     }
 }
 
+fn default_color_by_theme_search_match() -> ConfigColorByTheme {
+    ConfigColorByTheme {
+        dark: ConfigColor::BrightRed,
+        light: ConfigColor::Red,
+    }
+}
+
 fn default_theme_auto() -> ConfigTheme {
     ConfigTheme::Auto
 }
@@ -319,6 +326,8 @@ pub struct ConfigStyle {
     pub unit: ConfigColorByTheme,
     #[serde(default = "default_color_by_theme")]
     pub tree: ConfigColorByTheme,
+    #[serde(default = "default_color_by_theme_search_match")]
+    pub search_match: ConfigColorByTheme,
     #[serde(default)]
     pub by_percentage: ConfigStyleByPercentage,
     #[serde(default)]
@@ -333,6 +342,7 @@ impl Default for ConfigStyle {
             header: default_color_by_theme(),
             unit: default_color_by_theme(),
             tree: default_color_by_theme(),
+            search_match: default_color_by_theme_search_match(),
             by_percentage: Default::default(),
             by_state: Default::default(),
             by_unit: Default::default(),
@@ -500,6 +510,7 @@ impl Default for ConfigSearch {
 pub enum ConfigSearchKind {
     Exact,
     Partial,
+    Regex,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
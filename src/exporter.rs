@@ -0,0 +1,240 @@
+use crate::columns::{ConfigColumnKind, KIND_LIST};
+use crate::config::Config;
+use crate::view::View;
+use crate::Opt;
+use anyhow::{Context, Error};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::Duration;
+
+// ---------------------------------------------------------------------------------------------------------------------
+// Prometheus
+// ---------------------------------------------------------------------------------------------------------------------
+
+enum MetricType {
+    Counter,
+    Gauge,
+}
+
+const METRICS: &[(ConfigColumnKind, &str, MetricType)] = &[
+    (
+        ConfigColumnKind::UsageCpu,
+        "procs_cpu_usage_percent",
+        MetricType::Gauge,
+    ),
+    (
+        ConfigColumnKind::UsageMem,
+        "procs_memory_usage_percent",
+        MetricType::Gauge,
+    ),
+    (
+        ConfigColumnKind::CpuTime,
+        "procs_cpu_time_seconds_total",
+        MetricType::Counter,
+    ),
+    (
+        ConfigColumnKind::VmRss,
+        "procs_resident_memory_bytes",
+        MetricType::Gauge,
+    ),
+    (
+        ConfigColumnKind::VmSize,
+        "procs_virtual_memory_bytes",
+        MetricType::Gauge,
+    ),
+    (
+        ConfigColumnKind::ReadBytes,
+        "procs_read_bytes_per_second",
+        MetricType::Gauge,
+    ),
+    (
+        ConfigColumnKind::WriteBytes,
+        "procs_write_bytes_per_second",
+        MetricType::Gauge,
+    ),
+    (
+        ConfigColumnKind::Threads,
+        "procs_threads",
+        MetricType::Gauge,
+    ),
+    #[cfg(not(target_os = "windows"))]
+    (
+        ConfigColumnKind::ContextSw,
+        "procs_context_switches_total",
+        MetricType::Counter,
+    ),
+    (
+        ConfigColumnKind::MajFlt,
+        "procs_major_page_faults_total",
+        MetricType::Counter,
+    ),
+    #[cfg(not(target_os = "windows"))]
+    (
+        ConfigColumnKind::MinFlt,
+        "procs_minor_page_faults_total",
+        MetricType::Counter,
+    ),
+    (
+        ConfigColumnKind::StartTime,
+        "procs_start_time_seconds",
+        MetricType::Gauge,
+    ),
+];
+
+const LABELS: &[(ConfigColumnKind, &str)] = &[
+    (ConfigColumnKind::User, "user"),
+    (ConfigColumnKind::Command, "command"),
+    #[cfg(not(target_os = "windows"))]
+    (ConfigColumnKind::Docker, "docker"),
+];
+
+pub fn prometheus_kinds() -> Vec<ConfigColumnKind> {
+    let mut kinds = Vec::new();
+    for (kind, _) in LABELS {
+        kinds.push(kind.clone());
+    }
+    for (kind, _, _) in METRICS {
+        kinds.push(kind.clone());
+    }
+    kinds
+}
+
+fn escape_label(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+pub fn render_prometheus(view: &View) -> String {
+    let mut labels = HashMap::new();
+    for pid in &view.visible_pids {
+        let mut label = format!("pid=\"{}\"", pid);
+        if let Some(ppid) = view.ppids.get(pid) {
+            label.push_str(&format!(",ppid=\"{}\"", ppid));
+        }
+        for (kind, name) in LABELS {
+            if let Some(column) = view.get_column(kind) {
+                let value = column
+                    .value(*pid)
+                    .map(|x| x.to_string())
+                    .unwrap_or_default();
+                label.push_str(&format!(",{}=\"{}\"", name, escape_label(&value)));
+            }
+        }
+        labels.insert(*pid, label);
+    }
+
+    let mut ret = String::from("");
+    for (kind, name, metric_type) in METRICS {
+        let column = if let Some(x) = view.get_column(kind) {
+            x
+        } else {
+            continue;
+        };
+        let metric_type = match metric_type {
+            MetricType::Counter => "counter",
+            MetricType::Gauge => "gauge",
+        };
+        ret.push_str(&format!("# HELP {} {}\n", name, KIND_LIST[kind].1));
+        ret.push_str(&format!("# TYPE {} {}\n", name, metric_type));
+        for pid in &view.visible_pids {
+            if let Some(x) = column.value(*pid).and_then(|x| x.as_f64()) {
+                ret.push_str(&format!("{}{{{}}} {}\n", name, labels[pid], x));
+            }
+        }
+    }
+    ret
+}
+
+// ---------------------------------------------------------------------------------------------------------------------
+// HTTP server
+// ---------------------------------------------------------------------------------------------------------------------
+
+const CONTENT_TYPE_PROMETHEUS: &str = "text/plain; version=0.0.4; charset=utf-8";
+const CONTENT_TYPE_JSON: &str = "application/json";
+const CONTENT_TYPE_TEXT: &str = "text/plain; charset=utf-8";
+
+#[cfg_attr(tarpaulin, skip)]
+pub fn serve(opt: &Opt, config: &Config, addr: &str) -> Result<(), Error> {
+    let listener = TcpListener::bind(addr).context(format!("failed to bind ({})", addr))?;
+    serve_on(listener, opt, config, None);
+    Ok(())
+}
+
+/// Serve the connections of the listener
+///
+/// If `max_connections` is specified, serving is stopped after the connections are handled.
+pub fn serve_on(listener: TcpListener, opt: &Opt, config: &Config, max_connections: Option<usize>) {
+    let max_connections = max_connections.unwrap_or(usize::MAX);
+    for stream in listener.incoming().flatten().take(max_connections) {
+        // Ignore errors of each connection to keep serving
+        let _ = handle(stream, opt, config);
+    }
+}
+
+fn handle(mut stream: TcpStream, opt: &Opt, config: &Config) -> Result<(), Error> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+
+    // Skip request headers
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line.trim_end().is_empty() {
+            break;
+        }
+    }
+
+    let mut request = request.split_whitespace();
+    let method = request.next().unwrap_or("");
+    let path = request.next().unwrap_or("");
+    let path = path.split('?').next().unwrap_or("");
+
+    let (status, content_type, body) = match (method, path) {
+        ("GET", "/metrics") | ("GET", "/processes.json") => match collect(opt, config) {
+            Ok(view) if path == "/metrics" => {
+                ("200 OK", CONTENT_TYPE_PROMETHEUS, render_prometheus(&view))
+            }
+            Ok(view) => (
+                "200 OK",
+                CONTENT_TYPE_JSON,
+                serde_json::to_string(&view.json_contents())?,
+            ),
+            Err(x) => (
+                "500 Internal Server Error",
+                CONTENT_TYPE_TEXT,
+                format!("{}\n", x),
+            ),
+        },
+        ("GET", _) => (
+            "404 Not Found",
+            CONTENT_TYPE_TEXT,
+            String::from("Not Found\n"),
+        ),
+        _ => (
+            "405 Method Not Allowed",
+            CONTENT_TYPE_TEXT,
+            String::from("Method Not Allowed\n"),
+        ),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()?;
+    Ok(())
+}
+
+fn collect(opt: &Opt, config: &Config) -> Result<View, Error> {
+    let mut view = View::new(opt, config, false)?;
+    view.filter(opt, config);
+    Ok(view)
+}
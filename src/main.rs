@@ -1,8 +1,12 @@
 mod column;
 mod columns;
 mod config;
+mod exporter;
 mod process;
+mod ps;
+mod query;
 mod style;
+mod template;
 mod term_info;
 mod util;
 mod view;
@@ -11,17 +15,20 @@ mod watcher;
 use crate::column::Column;
 use crate::columns::*;
 use crate::config::*;
-use crate::util::{adjust, get_theme, lap};
+use crate::template::Template;
+use crate::term_info::TermInfo;
+use crate::util::{adjust, get_theme, lap, parse_duration, parse_path, parse_port, PortSpec};
 use crate::view::View;
 use crate::watcher::Watcher;
-use anyhow::{anyhow, Context, Error};
+use anyhow::{anyhow, bail, Context, Error};
 use console::Term;
 use std::cmp;
 use std::collections::HashMap;
 use std::fs;
 use std::io::Read;
+use std::path::PathBuf;
 use std::str::FromStr;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use structopt::{clap, StructOpt};
 use unicode_width::UnicodeWidthStr;
 
@@ -29,7 +36,7 @@ use unicode_width::UnicodeWidthStr;
 // Opt
 // ---------------------------------------------------------------------------------------------------------------------
 
-#[derive(Debug, StructOpt)]
+#[derive(Clone, Debug, StructOpt)]
 #[structopt(long_version(option_env!("LONG_VERSION").unwrap_or(env!("CARGO_PKG_VERSION"))))]
 #[structopt(setting(clap::AppSettings::ColoredHelp))]
 #[structopt(setting(clap::AppSettings::DeriveDisplayOrder))]
@@ -73,6 +80,95 @@ pub struct Opt {
     )]
     pub nor: bool,
 
+    /// Exclude processes matched with the keyword ( the same as `!keyword` )
+    #[structopt(short = "x", long = "exclude", number_of_values(1))]
+    pub exclude: Vec<String>,
+
+    /// Search keywords as regular expression
+    #[structopt(long = "regex")]
+    pub regex: bool,
+
+    /// Show processes older than the duration like "30s", "15m", "12h" and "7d"
+    #[structopt(
+        long = "older-than",
+        value_name = "duration",
+        parse(try_from_str = parse_duration)
+    )]
+    pub older_than: Option<Duration>,
+
+    /// Show processes newer than the duration like "30s", "15m", "12h" and "7d"
+    #[structopt(
+        long = "newer-than",
+        value_name = "duration",
+        parse(try_from_str = parse_duration)
+    )]
+    pub newer_than: Option<Duration>,
+
+    /// Show processes of the user ( effective or real )
+    #[structopt(
+        value_name = "user",
+        long = "user",
+        multiple(true),
+        number_of_values(1)
+    )]
+    pub user: Vec<String>,
+
+    /// Show processes of the user ID ( effective or real )
+    #[structopt(value_name = "uid", long = "uid", multiple(true), number_of_values(1))]
+    pub uid: Vec<u32>,
+
+    /// Show processes of the current user
+    #[structopt(long = "mine")]
+    pub mine: bool,
+
+    /// Show the process and the descendants
+    #[structopt(
+        value_name = "pid",
+        long = "descendants-of",
+        multiple(true),
+        number_of_values(1)
+    )]
+    pub descendants_of: Vec<i32>,
+
+    /// Show the process and the ancestors
+    #[structopt(
+        value_name = "pid",
+        long = "ancestors-of",
+        multiple(true),
+        number_of_values(1)
+    )]
+    pub ancestors_of: Vec<i32>,
+
+    /// Show processes listening on the port like "8080", "8080/tcp" and "53/udp"
+    #[structopt(
+        value_name = "port",
+        long = "port",
+        multiple(true),
+        number_of_values(1),
+        parse(try_from_str = parse_port)
+    )]
+    pub port: Vec<PortSpec>,
+
+    /// Show processes opening the file
+    #[structopt(
+        value_name = "path",
+        long = "file",
+        multiple(true),
+        number_of_values(1),
+        parse(from_os_str = parse_path)
+    )]
+    pub file: Vec<PathBuf>,
+
+    /// Show processes opening any file under the directory
+    #[structopt(
+        value_name = "path",
+        long = "file-prefix",
+        multiple(true),
+        number_of_values(1),
+        parse(from_os_str = parse_path)
+    )]
+    pub file_prefix: Vec<PathBuf>,
+
     /// Show list of kind
     #[structopt(short = "l", long = "list")]
     pub list: bool,
@@ -96,6 +192,14 @@ pub struct Opt {
     #[structopt(skip)]
     pub watch_mode: bool,
 
+    /// Serve metrics and process list over HTTP
+    #[structopt(
+        long = "serve",
+        value_name = "address",
+        conflicts_with_all(&["watch", "watch_interval"])
+    )]
+    pub serve: Option<String>,
+
     /// Insert column to slot
     #[structopt(
         value_name = "kind",
@@ -110,6 +214,16 @@ pub struct Opt {
     #[structopt(value_name = "kind", long = "only")]
     pub only: Option<String>,
 
+    /// Specify columns by ps-style fields like "pid,rss,args" ( unlike ps -O, replacing all columns )
+    #[structopt(
+        value_name = "fields",
+        short = "O",
+        long = "fields",
+        multiple(true),
+        number_of_values(1)
+    )]
+    pub fields: Vec<String>,
+
     /// Sort column by ascending
     #[structopt(
         value_name = "kind",
@@ -155,6 +269,45 @@ pub struct Opt {
     )]
     pub pager: Option<String>,
 
+    /// Output format
+    #[structopt(
+        long = "format",
+        possible_value = "json",
+        possible_value = "csv",
+        possible_value = "tsv",
+        possible_value = "prometheus",
+        possible_value = "html",
+        possible_value = "markdown"
+    )]
+    pub format: Option<String>,
+
+    /// Output template like "{pid}\t{user}\t{command}"
+    #[structopt(
+        long = "output-template",
+        value_name = "template",
+        conflicts_with_all(&["format", "watch", "watch_interval", "serve"])
+    )]
+    pub output_template: Option<String>,
+
+    /// Show matched PIDs only
+    #[structopt(
+        long = "pids",
+        conflicts_with_all(&["format", "output_template", "watch", "watch_interval", "serve"])
+    )]
+    pub pids: bool,
+
+    /// Delimiter of `--pids` output [default: newline]
+    #[structopt(long = "delimiter", value_name = "delimiter", requires = "pids")]
+    pub delimiter: Option<String>,
+
+    /// Suppress output and exit with 1 if no process is matched
+    #[structopt(
+        short = "q",
+        long = "quiet",
+        conflicts_with_all(&["format", "output_template", "watch", "watch_interval", "serve"])
+    )]
+    pub quiet: bool,
+
     /// Interval to calculate throughput
     #[structopt(long = "interval", default_value = "100", value_name = "millisec")]
     pub interval: u64,
@@ -239,7 +392,19 @@ fn check_old_config(s: &str, config: Result<Config, toml::de::Error>) -> Result<
 fn main() {
     let err = Term::stderr();
 
-    if let Err(x) = run() {
+    let args: Vec<_> = std::env::args_os().collect();
+    let mut opt = match Opt::from_iter_safe(&args) {
+        Ok(x) => x,
+        // clap exits with 1, which means that no process is matched at --pids/--quiet
+        Err(x) if x.use_stderr() && pids_requested(&args) => {
+            let _ = err.write_line(&x.message);
+            std::process::exit(2);
+        }
+        Err(x) => x.exit(),
+    };
+    opt.watch_mode = opt.watch || opt.watch_interval.is_some();
+
+    let status = run(&opt).unwrap_or_else(|x| {
         let mut cause = x.chain();
         let _ = err.write_line(&format!(
             "{} {}",
@@ -251,22 +416,61 @@ fn main() {
             let _ = err.write_line(&format!("  {} {}", console::style("caused by:").red(), x));
         }
 
-        std::process::exit(1);
+        // As the same as grep, 1 means that no process is matched at --pids/--quiet
+        if opt.pids || opt.quiet {
+            2
+        } else {
+            1
+        }
+    });
+    if status != 0 {
+        std::process::exit(status);
     }
 }
 
-#[cfg_attr(tarpaulin, skip)]
-fn run() -> Result<(), Error> {
-    let mut opt = Opt::from_args();
-    opt.watch_mode = opt.watch || opt.watch_interval.is_some();
+/// Check whether `--pids`/`--quiet` is given in the arguments which are failed to be parsed
+///
+/// Each argument is parsed alone, or with the next one if it requires a value,
+/// so that short flag clusters and values are interpreted by the definitions of Opt.
+fn pids_requested(args: &[std::ffi::OsString]) -> bool {
+    let mut i = 1;
+    while i < args.len() {
+        if args[i] == "--" {
+            break;
+        }
+        if !args[i].to_string_lossy().starts_with('-') {
+            i += 1;
+            continue;
+        }
+        let mut len = 1;
+        let mut ret = Opt::from_iter_safe(&[&args[0], &args[i]]);
+        if let Err(ref x) = ret {
+            if x.kind == clap::ErrorKind::EmptyValue && i + 1 < args.len() {
+                len = 2;
+                ret = Opt::from_iter_safe(&[&args[0], &args[i], &args[i + 1]]);
+            }
+        }
+        if let Ok(x) = ret {
+            if x.pids || x.quiet {
+                return true;
+            }
+        }
+        i += len;
+    }
+    false
+}
 
+#[cfg_attr(tarpaulin, skip)]
+/// Run procs and return the exit status
+fn run(opt: &Opt) -> Result<i32, Error> {
     if opt.config {
-        run_config()
+        run_config()?;
     } else if opt.list {
-        run_list()
-    } else if let Some(shell) = opt.completion {
+        let config = get_config()?;
+        run_list(opt, &config)?;
+    } else if let Some(ref shell) = opt.completion {
         let shell =
-            clap::Shell::from_str(&shell).map_err(|x| anyhow!("unknwon shell type: {}", x))?;
+            clap::Shell::from_str(shell).map_err(|x| anyhow!("unknwon shell type: {}", x))?;
         Opt::clap().gen_completions("procs", shell, "./");
         let path = match shell {
             clap::Shell::Bash => "./procs.bash",
@@ -276,19 +480,25 @@ fn run() -> Result<(), Error> {
             clap::Shell::Zsh => "./_procs",
         };
         println!("completion file is generated: {}", path);
-        return Ok(());
     } else {
         let config = get_config()?;
-        if opt.watch_mode {
+        if let Some(ref addr) = opt.serve {
+            run_serve(opt, &config, addr)?;
+        } else if opt.watch_mode {
             let interval = match opt.watch_interval {
                 Some(n) => (n * 1000.0).round() as u64,
                 None=> 1000,
             };
-            run_watch(&opt, &config, interval)
+            run_watch(opt, &config, interval)?;
+        } else if opt.pids || opt.quiet {
+            if !run_pids(opt, &config)? {
+                return Ok(1);
+            }
         } else {
-            run_default(&opt, &config)
+            run_default(opt, &config)?;
         }
     }
+    Ok(0)
 }
 
 fn run_config() -> Result<(), Error> {
@@ -299,7 +509,13 @@ fn run_config() -> Result<(), Error> {
     Ok(())
 }
 
-fn run_list() -> Result<(), Error> {
+fn run_list(opt: &Opt, config: &Config) -> Result<(), Error> {
+    match opt.format.as_deref() {
+        Some("json") => return run_list_json(config),
+        Some(x) => bail!("format \"{}\" is not supported with --list", x),
+        None => (),
+    }
+
     let mut width = 0;
     let mut list = Vec::new();
     let mut desc = HashMap::new();
@@ -323,11 +539,74 @@ fn run_list() -> Result<(), Error> {
     Ok(())
 }
 
+fn run_list_json(config: &Config) -> Result<(), Error> {
+    let mut kinds: Vec<_> = KIND_LIST.iter().collect();
+    kinds.sort_by_key(|(_, (name, _))| *name);
+
+    let mut list = Vec::new();
+    for (kind, (name, description)) in kinds {
+        let mut column = gen_column(
+            kind,
+            None,
+            &config.docker.path,
+            &config.display.separator,
+            config.display.abbr_sid,
+            &config.display.tree_symbols,
+        );
+        column.reset_width(None, config, None, None);
+        let header = column.display_header(&ConfigColumnAlign::Left, None, config);
+        let unit = column.display_unit(&ConfigColumnAlign::Left);
+        let value_type = column.value_type();
+
+        // List is used for ports only
+        let numeric = matches!(value_type, Some(x) if x != "Str");
+
+        // Columns matched by keywords in the configuration
+        let searchable = config
+            .columns
+            .iter()
+            .any(|c| &c.kind == kind && (c.numeric_search || c.nonnumeric_search));
+
+        list.push(serde_json::json!({
+            "kind": name,
+            "description": description,
+            "header": header.trim_end(),
+            "unit": unit.trim_end(),
+            "type": value_type,
+            "numeric": numeric,
+            "searchable": searchable,
+            "sortable": column.sortable(),
+            "available": column.available(),
+        }));
+    }
+
+    TermInfo::new(false).write_output(&serde_json::Value::Array(list).to_string())
+}
+
 #[cfg_attr(tarpaulin, skip)]
 fn run_watch(opt: &Opt, config: &Config, interval: u64) -> Result<(), Error> {
     Watcher::start(opt, config, interval)
 }
 
+#[cfg_attr(tarpaulin, skip)]
+fn run_serve(opt: &Opt, config: &Config, addr: &str) -> Result<(), Error> {
+    exporter::serve(opt, config, addr)
+}
+
+fn run_pids(opt: &Opt, config: &Config) -> Result<bool, Error> {
+    let mut view = View::new(opt, config, false)?;
+    view.filter(opt, config);
+
+    let pids = view.matched_pids();
+    if !opt.quiet && !pids.is_empty() {
+        let delimiter = opt.delimiter.as_deref().unwrap_or("\n");
+        let pids: Vec<_> = pids.iter().map(|x| x.to_string()).collect();
+        view.term_info.write_output(&pids.join(delimiter))?;
+    }
+
+    Ok(!pids.is_empty())
+}
+
 fn run_default(opt: &Opt, config: &Config) -> Result<(), Error> {
     let mut time = Instant::now();
 
@@ -351,7 +630,19 @@ fn run_default(opt: &Opt, config: &Config) -> Result<(), Error> {
         lap(&mut time, "Info: view.adjust");
     }
 
-    view.display(opt, config, &theme)?;
+    if let Some(ref template) = opt.output_template {
+        view.display_template(&Template::parse(template)?)?
+    } else {
+        match opt.format.as_deref() {
+            Some("json") => view.display_json()?,
+            Some("csv") => view.display_csv(opt, config, ',')?,
+            Some("tsv") => view.display_csv(opt, config, '\t')?,
+            Some("prometheus") => view.display_prometheus()?,
+            Some("html") => view.display_html(opt, config, &theme)?,
+            Some("markdown") => view.display_markdown(config)?,
+            _ => view.display(opt, config, &theme)?,
+        }
+    }
 
     if opt.debug {
         lap(&mut time, "Info: view.display");
@@ -428,7 +719,16 @@ mod tests {
 
     #[test]
     fn test_run_list() {
-        let ret = run_list();
+        let config: Config = toml::from_str(CONFIG_DEFAULT).unwrap();
+
+        let args = vec!["procs", "--list"];
+        let opt = Opt::from_iter(args.iter());
+        let ret = run_list(&opt, &config);
+        assert!(ret.is_ok());
+
+        let args = vec!["procs", "--list", "--format", "json"];
+        let opt = Opt::from_iter(args.iter());
+        let ret = run_list(&opt, &config);
         assert!(ret.is_ok());
     }
 
@@ -474,6 +774,441 @@ mod tests {
         assert!(ret.is_ok());
     }
 
+    #[test]
+    fn test_run_format() {
+        let mut config: Config = toml::from_str(CONFIG_DEFAULT).unwrap();
+        config.pager.mode = ConfigPagerMode::Disable;
+        config.display.theme = ConfigTheme::Dark;
+
+        let args = vec!["procs", "--format", "json"];
+        let opt = Opt::from_iter(args.iter());
+        let ret = run_default(&opt, &config);
+        assert!(ret.is_ok());
+
+        let args = vec!["procs", "--format", "json", "--tree"];
+        let opt = Opt::from_iter(args.iter());
+        let ret = run_default(&opt, &config);
+        assert!(ret.is_ok());
+
+        // Numbers are not quoted
+        let args = vec!["procs", "--format", "json", "-O", "pid,rss,args"];
+        let opt = Opt::from_iter(args.iter());
+        let mut view = View::new(&opt, &config, false).unwrap();
+        view.filter(&opt, &config);
+        let json = view.json_contents();
+        let procs = json.as_array().unwrap();
+        assert!(!procs.is_empty());
+        for x in procs {
+            assert!(x["Pid"].is_i64());
+            assert!(x["VmRss"].is_u64() || x["VmRss"].is_null());
+            assert!(x["Command"].is_string());
+        }
+
+        let args = vec!["procs", "--format", "csv"];
+        let opt = Opt::from_iter(args.iter());
+        let ret = run_default(&opt, &config);
+        assert!(ret.is_ok());
+
+        let args = vec!["procs", "--format", "tsv", "--no-header"];
+        let opt = Opt::from_iter(args.iter());
+        let ret = run_default(&opt, &config);
+        assert!(ret.is_ok());
+
+        let args = vec!["procs", "--format", "prometheus"];
+        let opt = Opt::from_iter(args.iter());
+        let ret = run_default(&opt, &config);
+        assert!(ret.is_ok());
+
+        let args = vec!["procs", "--format", "html", "--tree"];
+        let opt = Opt::from_iter(args.iter());
+        let ret = run_default(&opt, &config);
+        assert!(ret.is_ok());
+
+        let args = vec!["procs", "--format", "markdown"];
+        let opt = Opt::from_iter(args.iter());
+        let ret = run_default(&opt, &config);
+        assert!(ret.is_ok());
+    }
+
+    #[test]
+    fn test_run_query() {
+        let mut config: Config = toml::from_str(CONFIG_DEFAULT).unwrap();
+        config.pager.mode = ConfigPagerMode::Disable;
+        config.display.theme = ConfigTheme::Dark;
+
+        let args = vec![
+            "procs",
+            "--and",
+            "pid:1",
+            "rss>=0",
+            "cpu<=100",
+            "etime>=0s",
+            "command~",
+            "user:root",
+        ];
+        let opt = Opt::from_iter(args.iter());
+        let ret = run_default(&opt, &config);
+        assert!(ret.is_ok());
+    }
+
+    #[test]
+    fn test_run_regex() {
+        let mut config: Config = toml::from_str(CONFIG_DEFAULT).unwrap();
+        config.pager.mode = ConfigPagerMode::Disable;
+        config.display.theme = ConfigTheme::Dark;
+
+        let args = vec!["procs", "--regex", "^[a-z]+", "1$"];
+        let opt = Opt::from_iter(args.iter());
+        let ret = run_default(&opt, &config);
+        assert!(ret.is_ok());
+
+        let args = vec!["procs", "--regex", "(unclosed"];
+        let opt = Opt::from_iter(args.iter());
+        let ret = run_default(&opt, &config);
+        assert!(ret.is_err());
+    }
+
+    #[test]
+    fn test_run_range() {
+        let mut config: Config = toml::from_str(CONFIG_DEFAULT).unwrap();
+        config.pager.mode = ConfigPagerMode::Disable;
+        config.display.theme = ConfigTheme::Dark;
+
+        let args = vec!["procs", "--or", "1..100", ">=1000", "<1", "..10"];
+        let opt = Opt::from_iter(args.iter());
+        let ret = run_default(&opt, &config);
+        assert!(ret.is_ok());
+    }
+
+    #[test]
+    fn test_run_age() {
+        let mut config: Config = toml::from_str(CONFIG_DEFAULT).unwrap();
+        config.pager.mode = ConfigPagerMode::Disable;
+        config.display.theme = ConfigTheme::Dark;
+
+        let args = vec!["procs", "--older-than", "1s", "--newer-than", "7d"];
+        let opt = Opt::from_iter(args.iter());
+        let ret = run_default(&opt, &config);
+        assert!(ret.is_ok());
+
+        let args = vec!["procs", "--older-than", "1x"];
+        assert!(Opt::from_iter_safe(args.iter()).is_err());
+    }
+
+    fn visible_pids(args: &[&str], config: &Config) -> Vec<i32> {
+        let opt = Opt::from_iter(args.iter());
+        let mut view = View::new(&opt, config, false).unwrap();
+        view.filter(&opt, config);
+        let mut pids = view.visible_pids;
+        pids.sort_unstable();
+        pids
+    }
+
+    #[test]
+    fn test_run_user() {
+        let mut config: Config = toml::from_str(CONFIG_DEFAULT).unwrap();
+        config.pager.mode = ConfigPagerMode::Disable;
+        config.display.theme = ConfigTheme::Dark;
+
+        let args = vec!["procs", "--user", "root", "--uid", "0", "--mine"];
+        let opt = Opt::from_iter(args.iter());
+        let ret = run_default(&opt, &config);
+        assert!(ret.is_ok());
+
+        let args = vec!["procs", "--uid", "root"];
+        assert!(Opt::from_iter_safe(args.iter()).is_err());
+    }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn test_run_uid() {
+        let config: Config = toml::from_str(CONFIG_DEFAULT).unwrap();
+
+        let args = vec!["procs", "--uid", "0"];
+        let opt = Opt::from_iter(args.iter());
+        let mut view = View::new(&opt, &config, false).unwrap();
+        view.filter(&opt, &config);
+
+        // pid 1 is owned by root
+        assert!(view.visible_pids.contains(&1));
+        for pid in &view.visible_pids {
+            let uid = [ConfigColumnKind::Uid, ConfigColumnKind::UidReal]
+                .iter()
+                .filter_map(|x| view.get_column(x).and_then(|c| c.value(*pid)))
+                .any(|x| x.as_f64() == Some(0.0));
+            assert!(uid);
+        }
+
+        // The test itself is matched by --mine
+        let mut config = config;
+        config.display.show_self = true;
+        let own_pid = std::process::id() as i32;
+        assert!(visible_pids(&["procs", "--mine"], &config).contains(&own_pid));
+    }
+
+    #[test]
+    fn test_run_lineage() {
+        let mut config: Config = toml::from_str(CONFIG_DEFAULT).unwrap();
+        config.pager.mode = ConfigPagerMode::Disable;
+        config.display.theme = ConfigTheme::Dark;
+
+        let pid = std::process::id().to_string();
+        let args = vec!["procs", "--tree", "--descendants-of", "1"];
+        let opt = Opt::from_iter(args.iter());
+        let ret = run_default(&opt, &config);
+        assert!(ret.is_ok());
+
+        let args = vec!["procs", "--ancestors-of", &pid];
+        let opt = Opt::from_iter(args.iter());
+        let ret = run_default(&opt, &config);
+        assert!(ret.is_ok());
+
+        config.display.show_self = true;
+        let own_pid = std::process::id() as i32;
+
+        // A child process to be matched as a descendant
+        #[cfg(not(target_os = "windows"))]
+        let mut child = std::process::Command::new("sleep")
+            .arg("10")
+            .spawn()
+            .unwrap();
+
+        let args = vec!["procs", "--descendants-of", &pid];
+        let opt = Opt::from_iter(args.iter());
+        let mut view = View::new(&opt, &config, false).unwrap();
+        view.filter(&opt, &config);
+        let mut expected = view.descendant_pids(own_pid);
+        expected.push(own_pid);
+        expected.sort_unstable();
+        let mut actual = view.visible_pids.clone();
+        actual.sort_unstable();
+        assert_eq!(actual, expected);
+        #[cfg(not(target_os = "windows"))]
+        assert!(actual.contains(&(child.id() as i32)));
+
+        let args = vec!["procs", "--ancestors-of", &pid];
+        let opt = Opt::from_iter(args.iter());
+        let mut view = View::new(&opt, &config, false).unwrap();
+        view.filter(&opt, &config);
+        let mut expected = vec![own_pid];
+        let mut p = own_pid;
+        while let Some(x) = view.ppids.get(&p) {
+            if !view.ppids.contains_key(x) || expected.contains(x) {
+                break;
+            }
+            expected.push(*x);
+            p = *x;
+        }
+        expected.sort_unstable();
+        let mut actual = view.visible_pids.clone();
+        actual.sort_unstable();
+        assert_eq!(actual, expected);
+
+        #[cfg(not(target_os = "windows"))]
+        {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn test_run_port() {
+        let mut config: Config = toml::from_str(CONFIG_DEFAULT).unwrap();
+        config.pager.mode = ConfigPagerMode::Disable;
+        config.display.theme = ConfigTheme::Dark;
+
+        let args = vec![
+            "procs", "--port", "80", "--port", "53/udp", "--port", "8080/TCP",
+        ];
+        let opt = Opt::from_iter(args.iter());
+        let ret = run_default(&opt, &config);
+        assert!(ret.is_ok());
+
+        let args = vec!["procs", "--port", "80/sctp"];
+        assert!(Opt::from_iter_safe(args.iter()).is_err());
+        let args = vec!["procs", "--port", "65536"];
+        assert!(Opt::from_iter_safe(args.iter()).is_err());
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_run_file() {
+        let mut config: Config = toml::from_str(CONFIG_DEFAULT).unwrap();
+        config.pager.mode = ConfigPagerMode::Disable;
+        config.display.theme = ConfigTheme::Dark;
+
+        let args = vec!["procs", "--file", "/dev/null", "--file-prefix", "/tmp"];
+        let opt = Opt::from_iter(args.iter());
+        let ret = run_default(&opt, &config);
+        assert!(ret.is_ok());
+
+        // The test itself opens the file
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("procs_test_run_file_{}", std::process::id()));
+        let file = fs::File::create(&path).unwrap();
+        let own_pid = std::process::id() as i32;
+        config.display.show_self = true;
+
+        let path_str = path.to_string_lossy();
+        let dir_str = dir.to_string_lossy();
+        let other = format!("{}.other", path_str);
+        assert!(visible_pids(&["procs", "--file", &path_str], &config).contains(&own_pid));
+        assert!(visible_pids(&["procs", "--file-prefix", &dir_str], &config).contains(&own_pid));
+        assert!(!visible_pids(&["procs", "--file", &other], &config).contains(&own_pid));
+
+        drop(file);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_run_highlight() {
+        let mut config: Config = toml::from_str(CONFIG_DEFAULT).unwrap();
+        config.pager.mode = ConfigPagerMode::Disable;
+        config.display.theme = ConfigTheme::Dark;
+
+        let args = vec!["procs", "--or", "root", "1", "--tree"];
+        let opt = Opt::from_iter(args.iter());
+        let ret = run_default(&opt, &config);
+        assert!(ret.is_ok());
+
+        config.search.nonnumeric_search = ConfigSearchKind::Exact;
+        config.search.numeric_search = ConfigSearchKind::Partial;
+        let ret = run_default(&opt, &config);
+        assert!(ret.is_ok());
+    }
+
+    #[test]
+    fn test_run_exclude() {
+        let mut config: Config = toml::from_str(CONFIG_DEFAULT).unwrap();
+        config.pager.mode = ConfigPagerMode::Disable;
+        config.display.theme = ConfigTheme::Dark;
+
+        let args = vec!["procs", "--or", "1", "!root", "-x", "user:root"];
+        let opt = Opt::from_iter(args.iter());
+        let ret = run_default(&opt, &config);
+        assert!(ret.is_ok());
+
+        let args = vec!["procs", "!1"];
+        let opt = Opt::from_iter(args.iter());
+        let ret = run_default(&opt, &config);
+        assert!(ret.is_ok());
+    }
+
+    #[test]
+    fn test_run_pids() {
+        let mut config: Config = toml::from_str(CONFIG_DEFAULT).unwrap();
+        config.pager.mode = ConfigPagerMode::Disable;
+        config.display.theme = ConfigTheme::Dark;
+
+        let args = vec!["procs", "--pids", "--delimiter", ","];
+        let opt = Opt::from_iter(args.iter());
+        let ret = run_pids(&opt, &config);
+        assert!(ret.unwrap());
+
+        let args = vec!["procs", "--quiet", "--tree", "1"];
+        let opt = Opt::from_iter(args.iter());
+        let ret = run_pids(&opt, &config);
+        assert!(ret.unwrap());
+
+        let args = vec!["procs", "--pids", "--and", "procs_no_such_process"];
+        let opt = Opt::from_iter(args.iter());
+        let ret = run_pids(&opt, &config);
+        assert!(!ret.unwrap());
+    }
+
+    #[test]
+    fn test_pids_requested() {
+        let check = |args: &[&str]| {
+            let args: Vec<std::ffi::OsString> = args.iter().map(|x| x.into()).collect();
+            pids_requested(&args)
+        };
+        assert!(check(&["procs", "--pids", "--no-such-option"]));
+        assert!(check(&["procs", "-tq", "--no-such-option"]));
+        assert!(check(&["procs", "--watch-interval", "1", "--quiet"]));
+        assert!(!check(&["procs", "--no-such-option"]));
+        assert!(!check(&["procs", "-x", "q", "--no-such-option"]));
+        assert!(!check(&["procs", "--", "--pids"]));
+    }
+
+    #[test]
+    fn test_run_fields() {
+        let mut config: Config = toml::from_str(CONFIG_DEFAULT).unwrap();
+        config.pager.mode = ConfigPagerMode::Disable;
+        config.display.theme = ConfigTheme::Dark;
+
+        let args = vec![
+            "procs",
+            "-O",
+            "pid,%cpu,rss,vsz,etime,time,nlwp",
+            "-O",
+            "args=CMD",
+        ];
+        let opt = Opt::from_iter(args.iter());
+        let ret = run_default(&opt, &config);
+        assert!(ret.is_ok());
+
+        let args = vec!["procs", "-O", "pid,unknown"];
+        let opt = Opt::from_iter(args.iter());
+        let ret = run_default(&opt, &config);
+        assert!(ret.is_err());
+    }
+
+    #[test]
+    fn test_run_template() {
+        let mut config: Config = toml::from_str(CONFIG_DEFAULT).unwrap();
+        config.pager.mode = ConfigPagerMode::Disable;
+        config.display.theme = ConfigTheme::Dark;
+
+        let args = vec![
+            "procs",
+            "--output-template",
+            "{pid}\\t{ppid}\\t{usage_cpu}%\\t{VmRss}\\t{command}",
+        ];
+        let opt = Opt::from_iter(args.iter());
+        let ret = run_default(&opt, &config);
+        assert!(ret.is_ok());
+
+        let args = vec!["procs", "--output-template", "{unknown}"];
+        let opt = Opt::from_iter(args.iter());
+        let ret = run_default(&opt, &config);
+        assert!(ret.is_err());
+    }
+
+    #[test]
+    fn test_run_serve() {
+        use std::io::Write;
+        use std::net::{TcpListener, TcpStream};
+
+        let paths = ["/metrics", "/processes.json", "/unknown"];
+
+        // Port 0 assigns a free port
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let mut config: Config = toml::from_str(CONFIG_DEFAULT).unwrap();
+            config.display.theme = ConfigTheme::Dark;
+
+            let args = vec!["procs", "--serve", "127.0.0.1:0"];
+            let opt = Opt::from_iter(args.iter());
+            exporter::serve_on(listener, &opt, &config, Some(paths.len()));
+        });
+
+        for path in &paths {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            if *path == "/unknown" {
+                assert!(response.starts_with("HTTP/1.1 404"));
+            } else {
+                assert!(response.starts_with("HTTP/1.1 200"));
+            }
+        }
+
+        assert!(server.join().is_ok());
+    }
+
     #[test]
     fn test_run_tree() {
         let mut config: Config = toml::from_str(CONFIG_DEFAULT).unwrap();
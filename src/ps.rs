@@ -0,0 +1,141 @@
+use crate::columns::ConfigColumnKind;
+use crate::config::{
+    Config, ConfigColor, ConfigColorByTheme, ConfigColumn, ConfigColumnAlign, ConfigColumnStyle,
+};
+use anyhow::{bail, Error};
+
+// ---------------------------------------------------------------------------------------------------------------------
+// FIELD_LIST
+// ---------------------------------------------------------------------------------------------------------------------
+
+// ps field names ( and the aliases ) and the corresponding column kinds.
+// procs has no executable-name-only column, so `comm` is shown as `Command`.
+const FIELD_LIST: &[(&str, ConfigColumnKind)] = &[
+    ("pid", ConfigColumnKind::Pid),
+    ("ppid", ConfigColumnKind::Ppid),
+    ("user", ConfigColumnKind::User),
+    ("uname", ConfigColumnKind::User),
+    ("%cpu", ConfigColumnKind::UsageCpu),
+    ("pcpu", ConfigColumnKind::UsageCpu),
+    ("%mem", ConfigColumnKind::UsageMem),
+    ("pmem", ConfigColumnKind::UsageMem),
+    ("rss", ConfigColumnKind::VmRss),
+    ("rssize", ConfigColumnKind::VmRss),
+    ("vsz", ConfigColumnKind::VmSize),
+    ("vsize", ConfigColumnKind::VmSize),
+    ("etime", ConfigColumnKind::ElapsedTime),
+    ("time", ConfigColumnKind::CpuTime),
+    ("cputime", ConfigColumnKind::CpuTime),
+    #[cfg(not(target_os = "windows"))]
+    ("stat", ConfigColumnKind::State),
+    #[cfg(not(target_os = "windows"))]
+    ("tty", ConfigColumnKind::Tty),
+    #[cfg(not(target_os = "windows"))]
+    ("tt", ConfigColumnKind::Tty),
+    ("args", ConfigColumnKind::Command),
+    ("command", ConfigColumnKind::Command),
+    ("cmd", ConfigColumnKind::Command),
+    ("comm", ConfigColumnKind::Command),
+    ("nlwp", ConfigColumnKind::Threads),
+    ("thcount", ConfigColumnKind::Threads),
+    #[cfg(not(target_os = "windows"))]
+    ("pgid", ConfigColumnKind::Pgid),
+    #[cfg(not(target_os = "windows"))]
+    ("pgrp", ConfigColumnKind::Pgid),
+    #[cfg(target_os = "linux")]
+    ("sid", ConfigColumnKind::Session),
+    #[cfg(target_os = "linux")]
+    ("session", ConfigColumnKind::Session),
+    #[cfg(not(target_os = "windows"))]
+    ("ni", ConfigColumnKind::Nice),
+    #[cfg(not(target_os = "windows"))]
+    ("nice", ConfigColumnKind::Nice),
+    ("pri", ConfigColumnKind::Priority),
+    ("lstart", ConfigColumnKind::StartTime),
+    ("start", ConfigColumnKind::StartTime),
+    ("uid", ConfigColumnKind::Uid),
+    ("gid", ConfigColumnKind::Gid),
+    ("group", ConfigColumnKind::Group),
+];
+
+pub fn find_field_kind(field: &str) -> Option<ConfigColumnKind> {
+    let field = field.to_lowercase();
+    FIELD_LIST
+        .iter()
+        .find(|(name, _)| *name == field)
+        .map(|(_, kind)| kind.clone())
+}
+
+// ---------------------------------------------------------------------------------------------------------------------
+// gen_config_columns
+// ---------------------------------------------------------------------------------------------------------------------
+
+/// Generate columns from ps-style field specifications like "pid,rss,args"
+///
+/// As the same as ps, `field=header` renames the header, and the header extends to the end of the specification.
+pub fn gen_config_columns(specs: &[String], config: &Config) -> Result<Vec<ConfigColumn>, Error> {
+    let mut columns = Vec::new();
+    for spec in specs {
+        let (fields, header) = match spec.find('=') {
+            Some(i) => (&spec[..i], Some(spec[i + 1..].to_string())),
+            None => (spec.as_str(), None),
+        };
+
+        let fields: Vec<_> = fields
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|x| !x.is_empty())
+            .collect();
+        if fields.is_empty() {
+            bail!("field is not specified: {}", spec);
+        }
+
+        for (i, field) in fields.iter().enumerate() {
+            let kind = match find_field_kind(field) {
+                Some(x) => x,
+                None => bail!("field \"{}\" is not supported", field),
+            };
+            let mut column = gen_config_column(kind, config);
+            if i == fields.len() - 1 && header.is_some() {
+                column.header = header.clone();
+            }
+            columns.push(column);
+        }
+    }
+    Ok(columns)
+}
+
+fn gen_config_column(kind: ConfigColumnKind, config: &Config) -> ConfigColumn {
+    // Reuse the configured style and search setting if the kind is in the configuration
+    if let Some(c) = config.columns.iter().find(|c| c.kind == kind) {
+        return c.clone();
+    }
+
+    let (style, align) = match kind {
+        ConfigColumnKind::UsageCpu | ConfigColumnKind::UsageMem => {
+            (ConfigColumnStyle::ByPercentage, ConfigColumnAlign::Right)
+        }
+        ConfigColumnKind::VmRss | ConfigColumnKind::VmSize => {
+            (ConfigColumnStyle::ByUnit, ConfigColumnAlign::Left)
+        }
+        #[cfg(not(target_os = "windows"))]
+        ConfigColumnKind::State => (ConfigColumnStyle::ByState, ConfigColumnAlign::Left),
+        _ => (
+            ConfigColumnStyle::Fixed(ConfigColorByTheme {
+                dark: ConfigColor::BrightWhite,
+                light: ConfigColor::Black,
+            }),
+            ConfigColumnAlign::Left,
+        ),
+    };
+
+    ConfigColumn {
+        kind,
+        style,
+        numeric_search: false,
+        nonnumeric_search: false,
+        align,
+        max_width: None,
+        min_width: None,
+        header: None,
+    }
+}
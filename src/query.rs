@@ -0,0 +1,331 @@
+use crate::column::{Column, ColumnValue};
+use crate::columns::{ConfigColumnKind, KIND_LIST};
+use crate::ps;
+use std::ops::{Bound, RangeBounds};
+
+// ---------------------------------------------------------------------------------------------------------------------
+// FIELD_ALIAS_LIST
+// ---------------------------------------------------------------------------------------------------------------------
+
+// Short field names which are not ps field names
+const FIELD_ALIAS_LIST: &[(&str, ConfigColumnKind)] = &[
+    ("cpu", ConfigColumnKind::UsageCpu),
+    ("mem", ConfigColumnKind::UsageMem),
+    #[cfg(not(target_os = "windows"))]
+    ("tcp", ConfigColumnKind::TcpPort),
+    #[cfg(not(target_os = "windows"))]
+    ("udp", ConfigColumnKind::UdpPort),
+];
+
+// ---------------------------------------------------------------------------------------------------------------------
+// Query
+// ---------------------------------------------------------------------------------------------------------------------
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum QueryOp {
+    /// `:` numeric equality, or partial match of string
+    Match,
+    /// `=` exact equality
+    Equal,
+    /// `~` partial match of the raw value as string
+    Contain,
+    Greater,
+    GreaterEqual,
+    Less,
+    LessEqual,
+}
+
+/// Field-qualified keyword like `user:root`, `cpu>50` and `rss>=1G`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Query {
+    pub kind: ConfigColumnKind,
+    pub op: QueryOp,
+    pub value: String,
+}
+
+impl Query {
+    /// Parse a keyword as query
+    ///
+    /// `None` is returned if the keyword is not a query, and it should be searched as a plain keyword.
+    pub fn parse(keyword: &str) -> Option<Self> {
+        let pos = keyword.find(&[':', '=', '~', '<', '>'][..])?;
+        let (field, rest) = keyword.split_at(pos);
+        if !field
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '%')
+        {
+            return None;
+        }
+        let kind = Query::find_kind(field)?;
+
+        let (op, value) = if let Some(x) = rest.strip_prefix(">=") {
+            (QueryOp::GreaterEqual, x)
+        } else if let Some(x) = rest.strip_prefix("<=") {
+            (QueryOp::LessEqual, x)
+        } else if let Some(x) = rest.strip_prefix('>') {
+            (QueryOp::Greater, x)
+        } else if let Some(x) = rest.strip_prefix('<') {
+            (QueryOp::Less, x)
+        } else if let Some(x) = rest.strip_prefix('=') {
+            (QueryOp::Equal, x)
+        } else if let Some(x) = rest.strip_prefix('~') {
+            (QueryOp::Contain, x)
+        } else {
+            (QueryOp::Match, &rest[1..])
+        };
+        if value.is_empty() {
+            return None;
+        }
+
+        Some(Query {
+            kind,
+            op,
+            value: value.to_string(),
+        })
+    }
+
+    /// Find column kind by alias, ps field name or kind name ignoring case and `_`
+    pub fn find_kind(field: &str) -> Option<ConfigColumnKind> {
+        let field = field.to_lowercase();
+        if let Some((_, kind)) = FIELD_ALIAS_LIST.iter().find(|(x, _)| *x == field) {
+            return Some(kind.clone());
+        }
+        if let Some(kind) = ps::find_field_kind(&field) {
+            return Some(kind);
+        }
+        let field = field.replace('_', "");
+        for (k, (v, _)) in KIND_LIST.iter() {
+            if v.to_lowercase() == field {
+                return Some(k.clone());
+            }
+        }
+        None
+    }
+
+    pub fn matches(&self, column: &dyn Column, pid: i32, smart_case: bool) -> bool {
+        if let Some(value) = column.value(pid) {
+            self.matches_value(&value, smart_case)
+        } else {
+            false
+        }
+    }
+
+    fn matches_value(&self, value: &ColumnValue, smart_case: bool) -> bool {
+        if let ColumnValue::List(x) = value {
+            return x.iter().any(|x| self.matches_value(x, smart_case));
+        }
+
+        let ignore_case = smart_case && self.value.find(char::is_uppercase).is_none();
+        let contains = |x: &str| {
+            if ignore_case {
+                x.to_lowercase().contains(&self.value)
+            } else {
+                x.contains(&self.value)
+            }
+        };
+
+        let number = value.as_f64();
+        let threshold = Query::parse_number(&self.value, value);
+        match (&self.op, number, threshold) {
+            (QueryOp::Match, Some(x), Some(y)) => (x - y).abs() < f64::EPSILON,
+            (QueryOp::Match, None, _) => contains(&value.to_string()),
+            (QueryOp::Equal, Some(x), Some(y)) => (x - y).abs() < f64::EPSILON,
+            (QueryOp::Equal, None, _) => value.to_string() == self.value,
+            (QueryOp::Contain, _, _) => contains(&value.to_string()),
+            (QueryOp::Greater, Some(x), Some(y)) => x > y,
+            (QueryOp::GreaterEqual, Some(x), Some(y)) => x >= y,
+            (QueryOp::Less, Some(x), Some(y)) => x < y,
+            (QueryOp::LessEqual, Some(x), Some(y)) => x <= y,
+            _ => false,
+        }
+    }
+
+    /// Parse a number with the unit suffix for the value type
+    ///
+    /// `Bytes` accepts `K`, `M`, `G`, `T` and `P` ( 1024-based ), and `Duration` accepts `s`, `m`, `h` and `d`.
+    pub fn parse_number(s: &str, value: &ColumnValue) -> Option<f64> {
+        let s = s.trim();
+        let pos = s
+            .char_indices()
+            .find(|(i, c)| !(c.is_ascii_digit() || *c == '.' || (*i == 0 && *c == '-')))
+            .map(|(i, _)| i)
+            .unwrap_or_else(|| s.len());
+        let (number, suffix) = s.split_at(pos);
+        let number: f64 = number.parse().ok()?;
+
+        let scale = match value {
+            ColumnValue::Bytes(_) => {
+                let suffix = suffix.to_lowercase();
+                let suffix = suffix.trim_end_matches('b').trim_end_matches('i');
+                match suffix {
+                    "" => 1.0,
+                    "k" => 1024.0,
+                    "m" => 1024.0 * 1024.0,
+                    "g" => 1024.0 * 1024.0 * 1024.0,
+                    "t" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+                    "p" => 1024.0 * 1024.0 * 1024.0 * 1024.0 * 1024.0,
+                    _ => return None,
+                }
+            }
+            ColumnValue::Duration(_) => match suffix {
+                "" | "s" => 1.0,
+                "m" => 60.0,
+                "h" => 60.0 * 60.0,
+                "d" => 24.0 * 60.0 * 60.0,
+                _ => return None,
+            },
+            _ => match suffix {
+                "" => 1.0,
+                _ => return None,
+            },
+        };
+        Some(number * scale)
+    }
+}
+
+// ---------------------------------------------------------------------------------------------------------------------
+// NumericRange
+// ---------------------------------------------------------------------------------------------------------------------
+
+/// Numeric keyword like `1000..2000`, `1000..`, `..2000` and `>=1024`
+///
+/// `..` includes both ends.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NumericRange {
+    pub lower: Bound<f64>,
+    pub upper: Bound<f64>,
+}
+
+impl NumericRange {
+    pub fn parse(keyword: &str) -> Option<Self> {
+        let number = |s: &str| Query::parse_number(s, &ColumnValue::Int(0));
+
+        let (lower, upper) = if let Some(x) = keyword.strip_prefix(">=") {
+            (Bound::Included(number(x)?), Bound::Unbounded)
+        } else if let Some(x) = keyword.strip_prefix("<=") {
+            (Bound::Unbounded, Bound::Included(number(x)?))
+        } else if let Some(x) = keyword.strip_prefix('>') {
+            (Bound::Excluded(number(x)?), Bound::Unbounded)
+        } else if let Some(x) = keyword.strip_prefix('<') {
+            (Bound::Unbounded, Bound::Excluded(number(x)?))
+        } else if let Some(pos) = keyword.find("..") {
+            let (lower, upper) = (&keyword[..pos], &keyword[pos + 2..]);
+            if lower.is_empty() && upper.is_empty() {
+                return None;
+            }
+            let lower = if lower.is_empty() {
+                Bound::Unbounded
+            } else {
+                Bound::Included(number(lower)?)
+            };
+            let upper = if upper.is_empty() {
+                Bound::Unbounded
+            } else {
+                Bound::Included(number(upper)?)
+            };
+            (lower, upper)
+        } else {
+            return None;
+        };
+
+        Some(NumericRange { lower, upper })
+    }
+
+    pub fn contains(&self, column: &dyn Column, pid: i32) -> bool {
+        if let Some(value) = column.value(pid) {
+            self.contains_value(&value)
+        } else {
+            false
+        }
+    }
+
+    fn contains_value(&self, value: &ColumnValue) -> bool {
+        if let ColumnValue::List(x) = value {
+            return x.iter().any(|x| self.contains_value(x));
+        }
+        match value.as_f64() {
+            Some(x) => (self.lower, self.upper).contains(&x),
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let query = Query::parse("rss>=1G").unwrap();
+        assert_eq!(query.kind, ConfigColumnKind::VmRss);
+        assert_eq!(query.op, QueryOp::GreaterEqual);
+        assert_eq!(query.value, "1G");
+
+        let query = Query::parse("user:root").unwrap();
+        assert_eq!(query.kind, ConfigColumnKind::User);
+        assert_eq!(query.op, QueryOp::Match);
+
+        let query = Query::parse("UsageCpu>50").unwrap();
+        assert_eq!(query.kind, ConfigColumnKind::UsageCpu);
+        assert_eq!(query.op, QueryOp::Greater);
+
+        assert!(Query::parse("nginx").is_none());
+        assert!(Query::parse("unknown:nginx").is_none());
+        assert!(Query::parse("127.0.0.1:80").is_none());
+        assert!(Query::parse("user:").is_none());
+
+        let bytes = ColumnValue::Bytes(0);
+        let duration = ColumnValue::Duration(0);
+        assert_eq!(Query::parse_number("2GiB", &bytes), Some(2147483648.0));
+        assert_eq!(Query::parse_number("1m", &duration), Some(60.0));
+        assert_eq!(Query::parse_number("-5", &ColumnValue::Int(0)), Some(-5.0));
+        assert_eq!(Query::parse_number("1G", &ColumnValue::Int(0)), None);
+    }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn test_matches_value() {
+        let query = Query::parse("tcp:8080").unwrap();
+        let ports = ColumnValue::List(vec![ColumnValue::Int(80), ColumnValue::Int(8080)]);
+        assert!(query.matches_value(&ports, true));
+
+        let query = Query::parse("rss>=1G").unwrap();
+        assert!(query.matches_value(&ColumnValue::Bytes(2 * 1024 * 1024 * 1024), true));
+        assert!(!query.matches_value(&ColumnValue::Bytes(1024), true));
+
+        let query = Query::parse("command~nginx").unwrap();
+        assert!(query.matches_value(&ColumnValue::Str(String::from("Nginx: worker")), true));
+        assert!(!query.matches_value(&ColumnValue::Str(String::from("Nginx: worker")), false));
+
+        let query = Query::parse("state=R").unwrap();
+        assert!(query.matches_value(&ColumnValue::Str(String::from("R")), true));
+        assert!(!query.matches_value(&ColumnValue::Str(String::from("S")), true));
+    }
+
+    #[test]
+    fn test_numeric_range() {
+        let range = NumericRange::parse("1000..2000").unwrap();
+        assert!(range.contains_value(&ColumnValue::Int(1000)));
+        assert!(range.contains_value(&ColumnValue::Int(2000)));
+        assert!(!range.contains_value(&ColumnValue::Int(2001)));
+
+        let range = NumericRange::parse(">=1024").unwrap();
+        assert!(range.contains_value(&ColumnValue::Int(1024)));
+        assert!(range.contains_value(&ColumnValue::from(&u64::MAX)));
+        assert!(!range.contains_value(&ColumnValue::Int(1023)));
+
+        let range = NumericRange::parse("<10").unwrap();
+        assert!(range.contains_value(&ColumnValue::Float(9.5)));
+        assert!(!range.contains_value(&ColumnValue::Int(10)));
+
+        let range = NumericRange::parse("8000..").unwrap();
+        let ports = ColumnValue::List(vec![ColumnValue::Int(80), ColumnValue::Int(8080)]);
+        assert!(range.contains_value(&ports));
+        assert!(!range.contains_value(&ColumnValue::Str(String::from("8080"))));
+
+        assert!(NumericRange::parse("..").is_none());
+        assert!(NumericRange::parse("1..a").is_none());
+        assert!(NumericRange::parse(">1G").is_none());
+        assert!(NumericRange::parse("1000").is_none());
+    }
+}
@@ -4,81 +4,46 @@ use ansi_term::{
     Color::{self, Fixed},
     Style,
 };
-use lazy_static::lazy_static;
 
-lazy_static! {
-    static ref BRIGHT_BLACK: Style = Fixed(8).normal();
-    static ref BRIGHT_RED: Style = Fixed(9).normal();
-    static ref BRIGHT_GREEN: Style = Fixed(10).normal();
-    static ref BRIGHT_YELLOW: Style = Fixed(11).normal();
-    static ref BRIGHT_BLUE: Style = Fixed(12).normal();
-    static ref BRIGHT_MAGENTA: Style = Fixed(13).normal();
-    static ref BRIGHT_CYAN: Style = Fixed(14).normal();
-    static ref BRIGHT_WHITE: Style = Fixed(15).normal();
-    static ref BLACK: Style = Fixed(0).normal();
-    static ref RED: Style = Fixed(1).normal();
-    static ref GREEN: Style = Fixed(2).normal();
-    static ref YELLOW: Style = Fixed(3).normal();
-    static ref BLUE: Style = Fixed(4).normal();
-    static ref MAGENTA: Style = Fixed(5).normal();
-    static ref CYAN: Style = Fixed(6).normal();
-    static ref WHITE: Style = Fixed(7).normal();
-}
-
-fn apply_style_by_state<'a>(
-    x: String,
-    s: &ConfigStyle,
-    theme: &ConfigTheme,
-    faded: bool,
-) -> ANSIGenericString<'a, str> {
+fn select_color_by_state<'a>(x: &str, s: &'a ConfigStyle) -> &'a ConfigColorByTheme {
     match x {
-        ref x if x.contains('D') => apply_color(x.to_string(), &s.by_state.color_d, theme, faded),
-        ref x if x.contains('R') => apply_color(x.to_string(), &s.by_state.color_r, theme, faded),
-        ref x if x.contains('S') => apply_color(x.to_string(), &s.by_state.color_s, theme, faded),
-        ref x if x.contains('T') => apply_color(x.to_string(), &s.by_state.color_t, theme, faded),
-        ref x if x.contains('t') => apply_color(x.to_string(), &s.by_state.color_t, theme, faded),
-        ref x if x.contains('Z') => apply_color(x.to_string(), &s.by_state.color_z, theme, faded),
-        ref x if x.contains('X') => apply_color(x.to_string(), &s.by_state.color_x, theme, faded),
-        ref x if x.contains('K') => apply_color(x.to_string(), &s.by_state.color_k, theme, faded),
-        ref x if x.contains('W') => apply_color(x.to_string(), &s.by_state.color_w, theme, faded),
-        ref x if x.contains('P') => apply_color(x.to_string(), &s.by_state.color_p, theme, faded),
-        _ => apply_color(x, &s.by_state.color_x, theme, faded),
+        x if x.contains('D') => &s.by_state.color_d,
+        x if x.contains('R') => &s.by_state.color_r,
+        x if x.contains('S') => &s.by_state.color_s,
+        x if x.contains('T') => &s.by_state.color_t,
+        x if x.contains('t') => &s.by_state.color_t,
+        x if x.contains('Z') => &s.by_state.color_z,
+        x if x.contains('X') => &s.by_state.color_x,
+        x if x.contains('K') => &s.by_state.color_k,
+        x if x.contains('W') => &s.by_state.color_w,
+        x if x.contains('P') => &s.by_state.color_p,
+        _ => &s.by_state.color_x,
     }
 }
 
-fn apply_style_by_unit<'a>(
-    x: String,
-    s: &ConfigStyle,
-    theme: &ConfigTheme,
-    faded: bool,
-) -> ANSIGenericString<'a, str> {
+fn select_color_by_unit<'a>(x: &str, s: &'a ConfigStyle) -> &'a ConfigColorByTheme {
     match x {
-        ref x if x.contains('K') => apply_color(x.to_string(), &s.by_unit.color_k, theme, faded),
-        ref x if x.contains('M') => apply_color(x.to_string(), &s.by_unit.color_m, theme, faded),
-        ref x if x.contains('G') => apply_color(x.to_string(), &s.by_unit.color_g, theme, faded),
-        ref x if x.contains('T') => apply_color(x.to_string(), &s.by_unit.color_t, theme, faded),
-        ref x if x.contains('P') => apply_color(x.to_string(), &s.by_unit.color_p, theme, faded),
-        _ => apply_color(x, &s.by_unit.color_x, theme, faded),
+        x if x.contains('K') => &s.by_unit.color_k,
+        x if x.contains('M') => &s.by_unit.color_m,
+        x if x.contains('G') => &s.by_unit.color_g,
+        x if x.contains('T') => &s.by_unit.color_t,
+        x if x.contains('P') => &s.by_unit.color_p,
+        _ => &s.by_unit.color_x,
     }
 }
 
-fn apply_style_by_percentage<'a>(
-    x: String,
-    s: &ConfigStyle,
-    theme: &ConfigTheme,
-    faded: bool,
-) -> ANSIGenericString<'a, str> {
+fn select_color_by_percentage<'a>(x: &str, s: &'a ConfigStyle) -> &'a ConfigColorByTheme {
     let value: f64 = x.trim().parse().unwrap_or(0.0);
     if value > 100.0 {
-        apply_color(x, &s.by_percentage.color_100, theme, faded)
+        &s.by_percentage.color_100
     } else if value > 75.0 {
-        apply_color(x, &s.by_percentage.color_075, theme, faded)
+        &s.by_percentage.color_075
     } else if value > 50.0 {
-        apply_color(x, &s.by_percentage.color_050, theme, faded)
+        &s.by_percentage.color_050
     } else if value > 25.0 {
-        apply_color(x, &s.by_percentage.color_025, theme, faded)
+        &s.by_percentage.color_025
     } else {
-        apply_color(x, &s.by_percentage.color_000, theme, faded)
+        &s.by_percentage.color_000
     }
 }
 
@@ -94,60 +59,66 @@ fn hexcode2color(hexcode: &str) -> Style {
     Color::RGB(r, g, b).normal()
 }
 
+fn theme_color<'a>(c: &'a ConfigColorByTheme, theme: &ConfigTheme) -> &'a ConfigColor {
+    match theme {
+        ConfigTheme::Dark => &c.dark,
+        ConfigTheme::Light => &c.light,
+        _ => unreachable!(),
+    }
+}
+
+/// Index of 256 colors, or `None` for RGB color
+///
+/// Bright colors are faded to the normal colors.
+fn color_index(c: &ConfigColor, faded: bool) -> Option<u8> {
+    let bright = if faded { 0 } else { 8 };
+    let index = match c {
+        ConfigColor::BrightBlack => bright,
+        ConfigColor::BrightRed => bright + 1,
+        ConfigColor::BrightGreen => bright + 2,
+        ConfigColor::BrightYellow => bright + 3,
+        ConfigColor::BrightBlue => bright + 4,
+        ConfigColor::BrightMagenta => bright + 5,
+        ConfigColor::BrightCyan => bright + 6,
+        ConfigColor::BrightWhite => bright + 7,
+        ConfigColor::Black => 0,
+        ConfigColor::Red => 1,
+        ConfigColor::Green => 2,
+        ConfigColor::Yellow => 3,
+        ConfigColor::Blue => 4,
+        ConfigColor::Magenta => 5,
+        ConfigColor::Cyan => 6,
+        ConfigColor::White => 7,
+        ConfigColor::Color256(c) => *c,
+        ConfigColor::RGB(_) => return None,
+    };
+    Some(index)
+}
+
 pub fn apply_color<'a>(
     x: String,
     c: &ConfigColorByTheme,
     theme: &ConfigTheme,
     faded: bool,
 ) -> ANSIGenericString<'a, str> {
-    let c = match theme {
-        ConfigTheme::Dark => &c.dark,
-        ConfigTheme::Light => &c.light,
-        _ => unreachable!(),
-    };
+    let c = theme_color(c, theme);
+    match (color_index(c, faded), c) {
+        (Some(i), _) => Fixed(i).paint(x),
+        (None, ConfigColor::RGB(c)) => hexcode2color(c).paint(x),
+        (None, _) => unreachable!(),
+    }
+}
 
-    if faded {
-        match c {
-            ConfigColor::BrightBlack => BLACK.paint(x),
-            ConfigColor::BrightRed => RED.paint(x),
-            ConfigColor::BrightGreen => GREEN.paint(x),
-            ConfigColor::BrightYellow => YELLOW.paint(x),
-            ConfigColor::BrightBlue => BLUE.paint(x),
-            ConfigColor::BrightMagenta => MAGENTA.paint(x),
-            ConfigColor::BrightCyan => CYAN.paint(x),
-            ConfigColor::BrightWhite => WHITE.paint(x),
-            ConfigColor::Black => BLACK.paint(x),
-            ConfigColor::Red => RED.paint(x),
-            ConfigColor::Green => GREEN.paint(x),
-            ConfigColor::Yellow => YELLOW.paint(x),
-            ConfigColor::Blue => BLUE.paint(x),
-            ConfigColor::Magenta => MAGENTA.paint(x),
-            ConfigColor::Cyan => CYAN.paint(x),
-            ConfigColor::White => WHITE.paint(x),
-            ConfigColor::Color256(c) => Fixed(*c).paint(x),
-            ConfigColor::RGB(c) => hexcode2color(c).paint(x),
-        }
-    } else {
-        match c {
-            ConfigColor::BrightBlack => BRIGHT_BLACK.paint(x),
-            ConfigColor::BrightRed => BRIGHT_RED.paint(x),
-            ConfigColor::BrightGreen => BRIGHT_GREEN.paint(x),
-            ConfigColor::BrightYellow => BRIGHT_YELLOW.paint(x),
-            ConfigColor::BrightBlue => BRIGHT_BLUE.paint(x),
-            ConfigColor::BrightMagenta => BRIGHT_MAGENTA.paint(x),
-            ConfigColor::BrightCyan => BRIGHT_CYAN.paint(x),
-            ConfigColor::BrightWhite => BRIGHT_WHITE.paint(x),
-            ConfigColor::Black => BLACK.paint(x),
-            ConfigColor::Red => RED.paint(x),
-            ConfigColor::Green => GREEN.paint(x),
-            ConfigColor::Yellow => YELLOW.paint(x),
-            ConfigColor::Blue => BLUE.paint(x),
-            ConfigColor::Magenta => MAGENTA.paint(x),
-            ConfigColor::Cyan => CYAN.paint(x),
-            ConfigColor::White => WHITE.paint(x),
-            ConfigColor::Color256(c) => Fixed(*c).paint(x),
-            ConfigColor::RGB(c) => hexcode2color(c).paint(x),
-        }
+pub fn select_color<'a>(
+    x: &str,
+    cs: &'a ConfigColumnStyle,
+    s: &'a ConfigStyle,
+) -> &'a ConfigColorByTheme {
+    match cs {
+        ConfigColumnStyle::Fixed(c) => c,
+        ConfigColumnStyle::ByPercentage => select_color_by_percentage(x, s),
+        ConfigColumnStyle::ByState => select_color_by_state(x, s),
+        ConfigColumnStyle::ByUnit => select_color_by_unit(x, s),
     }
 }
 
@@ -158,11 +129,53 @@ pub fn apply_style<'a>(
     theme: &ConfigTheme,
     faded: bool,
 ) -> ANSIGenericString<'a, str> {
-    match cs {
-        ConfigColumnStyle::Fixed(c) => apply_color(x, c, theme, faded),
-        ConfigColumnStyle::ByPercentage => apply_style_by_percentage(x, s, theme, faded),
-        ConfigColumnStyle::ByState => apply_style_by_state(x, s, theme, faded),
-        ConfigColumnStyle::ByUnit => apply_style_by_unit(x, s, theme, faded),
+    let c = select_color(&x, cs, s);
+    apply_color(x, c, theme, faded)
+}
+
+// xterm default colors of 16 basic colors
+const BASIC_COLORS: [(u8, u8, u8); 16] = [
+    (0x00, 0x00, 0x00),
+    (0xcd, 0x00, 0x00),
+    (0x00, 0xcd, 0x00),
+    (0xcd, 0xcd, 0x00),
+    (0x00, 0x00, 0xee),
+    (0xcd, 0x00, 0xcd),
+    (0x00, 0xcd, 0xcd),
+    (0xe5, 0xe5, 0xe5),
+    (0x7f, 0x7f, 0x7f),
+    (0xff, 0x00, 0x00),
+    (0x00, 0xff, 0x00),
+    (0xff, 0xff, 0x00),
+    (0x5c, 0x5c, 0xff),
+    (0xff, 0x00, 0xff),
+    (0x00, 0xff, 0xff),
+    (0xff, 0xff, 0xff),
+];
+
+fn color256_to_css(c: u8) -> String {
+    let (r, g, b) = match c {
+        0..=15 => BASIC_COLORS[c as usize],
+        16..=231 => {
+            let level = |x: u8| if x == 0 { 0 } else { 55 + x * 40 };
+            let c = c - 16;
+            (level(c / 36), level(c / 6 % 6), level(c % 6))
+        }
+        _ => {
+            let x = 8 + (c - 232) * 10;
+            (x, x, x)
+        }
+    };
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Convert color to CSS color as the same as `apply_color`
+pub fn color_to_css(c: &ConfigColorByTheme, theme: &ConfigTheme, faded: bool) -> String {
+    let c = theme_color(c, theme);
+    match (color_index(c, faded), c) {
+        (Some(i), _) => color256_to_css(i),
+        (None, ConfigColor::RGB(c)) => c.clone(),
+        (None, _) => unreachable!(),
     }
 }
 
@@ -0,0 +1,120 @@
+use crate::columns::ConfigColumnKind;
+use crate::query::Query;
+use anyhow::{bail, Error};
+
+pub enum TemplateItem {
+    Text(String),
+    Column(ConfigColumnKind),
+}
+
+pub struct Template {
+    pub items: Vec<TemplateItem>,
+}
+
+impl Template {
+    /// Parse a template like "{pid}\t{user}\t{usage_cpu}%\t{command}"
+    ///
+    /// Column names are the same as the fields of field-qualified keywords.
+    /// `\t`, `\n` and `\\` are unescaped, and `{{` / `}}` are literal braces.
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        let mut items = Vec::new();
+        let mut text = String::from("");
+        let mut chars = s.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.peek() {
+                    Some('t') => {
+                        text.push('\t');
+                        chars.next();
+                    }
+                    Some('n') => {
+                        text.push('\n');
+                        chars.next();
+                    }
+                    Some('\\') => {
+                        text.push('\\');
+                        chars.next();
+                    }
+                    _ => text.push(c),
+                },
+                '{' if chars.peek() == Some(&'{') => {
+                    text.push('{');
+                    chars.next();
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    text.push('}');
+                    chars.next();
+                }
+                '{' => {
+                    let mut name = String::from("");
+                    let mut closed = false;
+                    for c in chars.by_ref() {
+                        if c == '}' {
+                            closed = true;
+                            break;
+                        }
+                        name.push(c);
+                    }
+                    if !closed {
+                        bail!("unclosed \"{{\" in template: {}", s);
+                    }
+                    if !text.is_empty() {
+                        items.push(TemplateItem::Text(text));
+                        text = String::from("");
+                    }
+                    match Query::find_kind(name.trim()) {
+                        Some(kind) => items.push(TemplateItem::Column(kind)),
+                        None => bail!("kind \"{}\" is not found in kind list", name),
+                    }
+                }
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            items.push(TemplateItem::Text(text));
+        }
+        Ok(Template { items })
+    }
+
+    pub fn kinds(&self) -> Vec<ConfigColumnKind> {
+        let mut kinds = Vec::new();
+        for item in &self.items {
+            if let TemplateItem::Column(kind) = item {
+                kinds.push(kind.clone());
+            }
+        }
+        kinds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let template = Template::parse("{pid}\\t{usage_cpu}% {{{Command}}} {cpu}").unwrap();
+        let mut text = Vec::new();
+        let mut kinds = Vec::new();
+        for item in &template.items {
+            match item {
+                TemplateItem::Text(x) => text.push(x.clone()),
+                TemplateItem::Column(x) => kinds.push(x.clone()),
+            }
+        }
+        assert_eq!(text, vec!["\t", "% {", "} "]);
+        assert_eq!(
+            kinds,
+            vec![
+                ConfigColumnKind::Pid,
+                ConfigColumnKind::UsageCpu,
+                ConfigColumnKind::Command,
+                ConfigColumnKind::UsageCpu
+            ]
+        );
+
+        assert!(Template::parse("{pid").is_err());
+        let err = Template::parse("{Unknown_Kind}").err().unwrap();
+        assert!(err.to_string().contains("\"Unknown_Kind\""));
+    }
+}
@@ -32,6 +32,21 @@ impl TermInfo {
         Ok(())
     }
 
+    /// Write a line of output ignoring `Broken pipe`
+    ///
+    /// `Broken pipe` may occur if the output is piped to `head` and so on.
+    pub fn write_output(&self, s: &str) -> Result<(), Error> {
+        match self.write_line(s) {
+            Err(x)
+                if x.downcast_ref::<std::io::Error>().map(|x| x.kind())
+                    == Some(std::io::ErrorKind::BrokenPipe) =>
+            {
+                Ok(())
+            }
+            x => x,
+        }
+    }
+
     pub fn clear_screen(&self) -> Result<(), Error> {
         self.term.clear_screen()?;
         Ok(())
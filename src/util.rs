@@ -1,9 +1,15 @@
-use crate::column::Column;
+use crate::column::{Column, ColumnValue};
 use crate::columns::{ConfigColumnKind, KIND_LIST};
 use crate::config::{Config, ConfigColumnAlign, ConfigSearchLogic, ConfigTheme};
+use crate::query::{NumericRange, Query};
 use crate::Opt;
+use anyhow::{Context, Error};
 use byte_unit::Byte;
+use regex::{Regex, RegexBuilder};
 use std::borrow::Cow;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::PathBuf;
 use std::time::Duration;
 use std::time::Instant;
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
@@ -13,13 +19,10 @@ pub enum KeywordClass {
     NonNumeric,
 }
 
-pub fn find_partial<T: AsRef<str>>(
-    columns: &[&dyn Column],
-    pid: i32,
-    keyword: &[T],
-    logic: &ConfigSearchLogic,
-    smart_case: bool,
-) -> bool {
+/// Combine whether each keyword is matched by the logic
+///
+/// NAND and NOR are combined as AND and OR, and the result is inverted by the caller.
+fn find_keyword<T>(keyword: &[T], logic: &ConfigSearchLogic, hit: impl Fn(&T) -> bool) -> bool {
     let mut ret = match logic {
         ConfigSearchLogic::And => true,
         ConfigSearchLogic::Or => false,
@@ -27,15 +30,7 @@ pub fn find_partial<T: AsRef<str>>(
         ConfigSearchLogic::Nor => false,
     };
     for w in keyword {
-        let mut hit = false;
-        let word = w.as_ref();
-        let ignore_case = smart_case && word.find(char::is_uppercase).is_none();
-        for c in columns {
-            if c.find_partial(pid, word, ignore_case) {
-                hit = true;
-                break;
-            }
-        }
+        let hit = hit(w);
         ret = match logic {
             ConfigSearchLogic::And => ret & hit,
             ConfigSearchLogic::Or => ret | hit,
@@ -46,34 +41,124 @@ pub fn find_partial<T: AsRef<str>>(
     ret
 }
 
+pub fn find_partial<T: AsRef<str>>(
+    columns: &[&dyn Column],
+    pid: i32,
+    keyword: &[T],
+    logic: &ConfigSearchLogic,
+    smart_case: bool,
+) -> bool {
+    find_keyword(keyword, logic, |w| {
+        let word = w.as_ref();
+        let ignore_case = smart_case && word.find(char::is_uppercase).is_none();
+        columns
+            .iter()
+            .any(|c| c.find_partial(pid, word, ignore_case))
+    })
+}
+
 pub fn find_exact<T: AsRef<str>>(
     columns: &[&dyn Column],
     pid: i32,
     keyword: &[T],
     logic: &ConfigSearchLogic,
 ) -> bool {
-    let mut ret = match logic {
-        ConfigSearchLogic::And => true,
-        ConfigSearchLogic::Or => false,
-        ConfigSearchLogic::Nand => true,
-        ConfigSearchLogic::Nor => false,
-    };
-    for w in keyword {
-        let mut hit = false;
-        for c in columns {
-            if c.find_exact(pid, w.as_ref()) {
-                hit = true;
-                break;
-            }
+    find_keyword(keyword, logic, |w| {
+        columns.iter().any(|c| c.find_exact(pid, w.as_ref()))
+    })
+}
+
+pub fn find_regex(
+    columns: &[&dyn Column],
+    pid: i32,
+    keyword: &[Regex],
+    logic: &ConfigSearchLogic,
+) -> bool {
+    find_keyword(keyword, logic, |w| {
+        columns.iter().any(|c| c.find_regex(pid, w))
+    })
+}
+
+/// Compile a keyword as regular expression
+///
+/// If `smart_case` is enabled and the pattern has no uppercase literal, it is matched case-insensitively.
+/// Uppercase characters escaped by `\` ( ex. `\S` ) are not literal, so they are ignored.
+pub fn compile_regex(keyword: &str, smart_case: bool) -> Result<Regex, Error> {
+    let mut has_uppercase = false;
+    let mut escaped = false;
+    for c in keyword.chars() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c.is_uppercase() {
+            has_uppercase = true;
         }
-        ret = match logic {
-            ConfigSearchLogic::And => ret & hit,
-            ConfigSearchLogic::Or => ret | hit,
-            ConfigSearchLogic::Nand => ret & hit,
-            ConfigSearchLogic::Nor => ret | hit,
-        };
     }
-    ret
+    let re = RegexBuilder::new(keyword)
+        .case_insensitive(smart_case && !has_uppercase)
+        .build()
+        .context(format!("failed to compile regex ({})", keyword))?;
+    Ok(re)
+}
+
+pub fn find_query(
+    queries: &[(Query, Option<&dyn Column>)],
+    pid: i32,
+    logic: &ConfigSearchLogic,
+    smart_case: bool,
+) -> bool {
+    find_keyword(queries, logic, |(q, c)| match c {
+        Some(c) => q.matches(*c, pid, smart_case),
+        None => false,
+    })
+}
+
+pub fn find_range(
+    columns: &[&dyn Column],
+    pid: i32,
+    ranges: &[NumericRange],
+    logic: &ConfigSearchLogic,
+) -> bool {
+    find_keyword(ranges, logic, |r| {
+        columns.iter().any(|c| r.contains(*c, pid))
+    })
+}
+
+/// Parse a duration like "30s", "15m", "12h" and "7d" ( second without suffix )
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    match Query::parse_number(s, &ColumnValue::Duration(0)) {
+        Some(x) if (0.0..u64::MAX as f64).contains(&x) => Ok(Duration::from_secs_f64(x)),
+        _ => Err(format!("invalid duration: {}", s)),
+    }
+}
+
+/// Port specification like "8080", "8080/tcp" and "53/udp"
+#[derive(Clone, Debug, PartialEq)]
+pub struct PortSpec {
+    pub port: u16,
+    pub tcp: bool,
+    pub udp: bool,
+}
+
+pub fn parse_port(s: &str) -> Result<PortSpec, String> {
+    let (port, tcp, udp) = match s.find('/') {
+        Some(i) => match s[i + 1..].to_lowercase().as_str() {
+            "tcp" => (&s[..i], true, false),
+            "udp" => (&s[..i], false, true),
+            _ => return Err(format!("invalid protocol: {}", &s[i + 1..])),
+        },
+        None => (s, true, true),
+    };
+    let port = port
+        .parse()
+        .map_err(|_| format!("invalid port: {}", port))?;
+    Ok(PortSpec { port, tcp, udp })
+}
+
+/// Resolve a path to compare with open files, and keep it as is if it doesn't exist
+pub fn parse_path(s: &OsStr) -> PathBuf {
+    fs::canonicalize(s).unwrap_or_else(|_| PathBuf::from(s))
 }
 
 pub fn classify(keyword: &str) -> KeywordClass {
@@ -162,6 +247,36 @@ pub fn truncate(s: &'_ str, width: usize) -> Cow<'_, str> {
     }
 }
 
+pub fn escape_csv(s: &'_ str, delimiter: char) -> Cow<'_, str> {
+    if s.contains(&[delimiter, '"', '\n', '\r'][..]) {
+        Cow::Owned(format!("\"{}\"", s.replace("\"", "\"\"")))
+    } else {
+        Cow::Borrowed(s)
+    }
+}
+
+pub fn escape_html(s: &str) -> String {
+    let mut ret = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => ret.push_str("&amp;"),
+            '<' => ret.push_str("&lt;"),
+            '>' => ret.push_str("&gt;"),
+            '"' => ret.push_str("&quot;"),
+            c => ret.push(c),
+        }
+    }
+    ret
+}
+
+pub fn escape_markdown(s: &str) -> String {
+    // Line breaks in a cell break the table row
+    s.replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace(&['\r', '\n'][..], "<br>")
+}
+
 pub fn find_column_kind(pat: &str) -> Option<ConfigColumnKind> {
     for (k, (v, _)) in KIND_LIST.iter() {
         if v.to_lowercase().find(&pat.to_lowercase()).is_some() {
@@ -271,3 +386,63 @@ pub fn get_theme(opt: &Opt, config: &Config) -> ConfigTheme {
         x => x,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("10m"), Ok(Duration::from_secs(600)));
+        assert_eq!(parse_duration("1.5h"), Ok(Duration::from_secs(5400)));
+        assert_eq!(parse_duration("7d"), Ok(Duration::from_secs(604800)));
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("abc").is_err());
+        assert!(parse_duration("1w").is_err());
+        assert!(parse_duration("-1m").is_err());
+        assert!(parse_duration("1.2.3").is_err());
+        assert!(parse_duration("100000000000000000000000d").is_err());
+    }
+
+    #[test]
+    fn test_parse_port() {
+        let spec = |port, tcp, udp| Ok(PortSpec { port, tcp, udp });
+        assert_eq!(parse_port("8080"), spec(8080, true, true));
+        assert_eq!(parse_port("8080/tcp"), spec(8080, true, false));
+        assert_eq!(parse_port("53/UDP"), spec(53, false, true));
+        assert!(parse_port("").is_err());
+        assert!(parse_port("http").is_err());
+        assert!(parse_port("-1").is_err());
+        assert!(parse_port("70000").is_err());
+        assert!(parse_port("80/").is_err());
+        assert!(parse_port("80/sctp").is_err());
+        assert!(parse_port("/tcp").is_err());
+    }
+
+    #[test]
+    fn test_escape_csv() {
+        assert_eq!(escape_csv("abc", ','), "abc");
+        assert_eq!(escape_csv("a,b", ','), "\"a,b\"");
+        assert_eq!(escape_csv("a,b", '\t'), "a,b");
+        assert_eq!(escape_csv("a\tb", '\t'), "\"a\tb\"");
+        assert_eq!(escape_csv("a \"b\"", ','), "\"a \"\"b\"\"\"");
+        assert_eq!(escape_csv("a\nb", ','), "\"a\nb\"");
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(escape_html("abc"), "abc");
+        assert_eq!(
+            escape_html("<a href=\"x\">&</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn test_escape_markdown() {
+        assert_eq!(escape_markdown("a|b\\c"), "a\\|b\\\\c");
+        assert_eq!(escape_markdown("a\nb\r\nc\rd"), "a<br>b<br>c<br>d");
+    }
+}
@@ -1,15 +1,26 @@
 use crate::column::Column;
 use crate::columns::*;
 use crate::config::*;
-use crate::process::collect_proc;
-use crate::style::{apply_color, apply_style, color_to_column_style};
+use crate::exporter;
+use crate::process::{collect_proc, ProcessInfo};
+use crate::ps;
+use crate::query::{NumericRange, Query};
+use crate::style::{apply_color, apply_style, color_to_column_style, color_to_css, select_color};
+use crate::template::{Template, TemplateItem};
 use crate::term_info::TermInfo;
-use crate::util::{classify, find_column_kind, find_exact, find_partial, truncate, KeywordClass};
+use crate::util::{
+    classify, compile_regex, escape_csv, escape_html, escape_markdown, find_column_kind,
+    find_exact, find_partial, find_query, find_range, find_regex, truncate, KeywordClass, PortSpec,
+};
 use crate::Opt;
 use anyhow::{bail, Error};
 #[cfg(not(target_os = "windows"))]
 use pager::Pager;
+#[cfg(target_os = "linux")]
+use procfs::process::FDTarget;
+use regex::Regex;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::time::Duration;
 
 pub struct SortInfo {
@@ -19,15 +30,56 @@ pub struct SortInfo {
 
 pub struct View {
     pub columns: Vec<ColumnInfo>,
+    pub hidden_columns: HashMap<ConfigColumnKind, Box<dyn Column>>,
     pub term_info: TermInfo,
     pub sort_info: SortInfo,
     pub visible_pids: Vec<i32>,
     pub auxiliary_pids: Vec<i32>,
     pub ppids: HashMap<i32, i32>,
+    pub open_files: HashMap<i32, Vec<PathBuf>>,
+    pub selected_pid: Option<i32>,
+    pub scroll: usize,
+    highlight: Highlight,
+}
+
+/// Patterns to highlight matched keywords in columns matching to numeric or non-numeric keyword
+#[derive(Default)]
+struct Highlight {
+    numeric: Vec<Regex>,
+    nonnumeric: Vec<Regex>,
+    ranges: Vec<NumericRange>,
+    queries: Vec<Query>,
+    smart_case: bool,
+}
+
+// Columns of effective and real user
+const USER_KINDS: &[ConfigColumnKind] = &[
+    ConfigColumnKind::User,
+    #[cfg(not(target_os = "windows"))]
+    ConfigColumnKind::UserReal,
+];
+
+// Columns of effective and real user ID
+const UID_KINDS: &[ConfigColumnKind] = &[
+    ConfigColumnKind::Uid,
+    #[cfg(not(target_os = "windows"))]
+    ConfigColumnKind::UidReal,
+];
+
+/// Keywords classified by the search kind
+struct SearchKeyword<'a> {
+    numeric: Vec<&'a str>,
+    nonnumeric: Vec<&'a str>,
+    regex_numeric: Vec<Regex>,
+    regex_nonnumeric: Vec<Regex>,
+    ranges: Vec<NumericRange>,
+    queries: Vec<(Query, Option<&'a dyn Column>)>,
 }
 
 impl View {
     pub fn new(opt: &Opt, config: &Config, clear_by_line: bool) -> Result<Self, Error> {
+        View::check_regex(opt, config)?;
+
         let mut slot_idx = 0;
         let mut columns = Vec::new();
         if opt.tree {
@@ -56,7 +108,15 @@ impl View {
 
         let mut only_kind_found = false;
 
-        for c in &config.columns {
+        let ps_columns;
+        let config_columns = if opt.fields.is_empty() {
+            &config.columns
+        } else {
+            ps_columns = ps::gen_config_columns(&opt.fields, config)?;
+            &ps_columns
+        };
+
+        for c in config_columns {
             let kind = match &c.kind {
                 ConfigColumnKind::Slot => {
                     let kind = if let Some(insert) = opt.insert.get(slot_idx) {
@@ -109,6 +169,25 @@ impl View {
             }
         }
 
+        // Columns which are required by output format, but not shown
+        let mut hidden_columns = HashMap::new();
+        for kind in View::hidden_kinds(opt)? {
+            if columns.iter().any(|c| c.kind == kind) {
+                continue;
+            }
+            let column = gen_column(
+                &kind,
+                None,
+                &config.docker.path,
+                &config.display.separator,
+                config.display.abbr_sid,
+                &config.display.tree_symbols,
+            );
+            if column.available() {
+                hidden_columns.insert(kind, column);
+            }
+        }
+
         let show_thread = if opt.thread {
             true
         } else if opt.tree {
@@ -123,29 +202,92 @@ impl View {
                 c.column.add(&p);
             }
         }
+        for c in hidden_columns.values_mut() {
+            for p in &proc {
+                c.add(p);
+            }
+        }
 
         let mut ppids = HashMap::new();
         for p in &proc {
             ppids.insert(p.pid, p.ppid);
         }
 
+        // Reading fds of all processes is slow, so open files are collected only if required
+        let open_files = if opt.file.is_empty() && opt.file_prefix.is_empty() {
+            HashMap::new()
+        } else {
+            View::collect_open_files(&proc)?
+        };
+
         let term_info = TermInfo::new(clear_by_line);
         let mut sort_info = View::get_sort_info(opt, config, &columns);
 
-        if opt.only.is_some() {
+        if opt.only.is_some() || sort_info.idx >= columns.len() {
             sort_info.idx = 0;
         }
 
         Ok(View {
             columns,
+            hidden_columns,
             term_info,
             sort_info,
             visible_pids: vec![],
             auxiliary_pids: vec![],
             ppids,
+            open_files,
+            selected_pid: None,
+            scroll: 0,
+            highlight: Default::default(),
         })
     }
 
+    fn hidden_kinds(opt: &Opt) -> Result<Vec<ConfigColumnKind>, Error> {
+        let mut kinds = Vec::new();
+        if opt.serve.is_some() || opt.format.as_deref() == Some("prometheus") {
+            kinds.append(&mut exporter::prometheus_kinds());
+        }
+        kinds.append(&mut View::query_kinds(opt));
+        if let Some(ref template) = opt.output_template {
+            kinds.append(&mut Template::parse(template)?.kinds());
+        }
+        if opt.older_than.is_some() || opt.newer_than.is_some() {
+            kinds.push(ConfigColumnKind::ElapsedTime);
+        }
+        if !opt.user.is_empty() || opt.mine {
+            kinds.extend_from_slice(USER_KINDS);
+        }
+        if !opt.uid.is_empty() || opt.mine {
+            kinds.extend_from_slice(UID_KINDS);
+        }
+        if !opt.port.is_empty() {
+            #[cfg(target_os = "windows")]
+            bail!("--port is not supported on Windows");
+            #[cfg(not(target_os = "windows"))]
+            kinds.extend_from_slice(&[ConfigColumnKind::TcpPort, ConfigColumnKind::UdpPort]);
+        }
+        Ok(kinds)
+    }
+
+    /// Column kinds required by field-qualified keywords
+    pub fn query_kinds(opt: &Opt) -> Vec<ConfigColumnKind> {
+        let (include, exclude) = View::split_keywords(opt);
+        include
+            .iter()
+            .chain(exclude.iter())
+            .filter_map(|k| Query::parse(k))
+            .map(|query| query.kind)
+            .collect()
+    }
+
+    pub fn get_column(&self, kind: &ConfigColumnKind) -> Option<&dyn Column> {
+        if let Some(c) = self.columns.iter().find(|c| &c.kind == kind) {
+            Some(c.column.as_ref())
+        } else {
+            self.hidden_columns.get(kind).map(|c| c.as_ref())
+        }
+    }
+
     pub fn filter(&mut self, opt: &Opt, config: &Config) {
         let mut cols_nonnumeric = Vec::new();
         let mut cols_numeric = Vec::new();
@@ -158,15 +300,11 @@ impl View {
             }
         }
 
-        let mut keyword_nonnumeric = Vec::new();
-        let mut keyword_numeric = Vec::new();
-
-        for k in &opt.keyword {
-            match classify(k) {
-                KeywordClass::Numeric => keyword_numeric.push(k),
-                KeywordClass::NonNumeric => keyword_nonnumeric.push(k),
-            }
-        }
+        let search = View::search_config(opt, config);
+        let (include, exclude) = View::split_keywords(opt);
+        let include_keyword = self.search_keyword(&include, &search);
+        let exclude_keyword = self.search_keyword(&exclude, &search);
+        let highlight = View::highlight_pattern(&include_keyword, &search);
 
         let pids = self.columns[self.sort_info.idx]
             .column
@@ -174,34 +312,35 @@ impl View {
 
         let self_pid = std::process::id() as i32;
 
-        let logic = if opt.and {
-            ConfigSearchLogic::And
-        } else if opt.or {
-            ConfigSearchLogic::Or
-        } else if opt.nand {
-            ConfigSearchLogic::Nand
-        } else if opt.nor {
-            ConfigSearchLogic::Nor
-        } else {
-            config.search.logic.clone()
-        };
+        let logic = View::search_logic(opt, config);
 
         let mut candidate_pids = Vec::new();
         for pid in &pids {
-            let candidate = if !config.display.show_self && *pid == self_pid {
+            let candidate = if (!config.display.show_self && *pid == self_pid)
+                || !self.match_filter(opt, *pid)
+            {
                 false
-            } else if opt.keyword.is_empty() {
-                true
             } else {
-                View::search(
-                    *pid,
-                    &keyword_numeric,
-                    &keyword_nonnumeric,
-                    cols_numeric.as_slice(),
-                    cols_nonnumeric.as_slice(),
-                    &config,
-                    &logic,
-                )
+                // Excluded keywords are combined by OR regardless of the logic of included keywords
+                let included = include.is_empty()
+                    || View::search(
+                        *pid,
+                        &include_keyword,
+                        cols_numeric.as_slice(),
+                        cols_nonnumeric.as_slice(),
+                        &search,
+                        &logic,
+                    );
+                let excluded = !exclude.is_empty()
+                    && View::search(
+                        *pid,
+                        &exclude_keyword,
+                        cols_numeric.as_slice(),
+                        cols_nonnumeric.as_slice(),
+                        &search,
+                        &ConfigSearchLogic::Or,
+                    );
+                included && !excluded
             };
 
             if candidate {
@@ -231,14 +370,11 @@ impl View {
             if candidate_pids.contains(pid) {
                 visible_pids.push(*pid);
             }
-
-            if opt.watch_mode && visible_pids.len() >= self.term_info.height - 5 {
-                break;
-            }
         }
 
         self.visible_pids = visible_pids;
         self.auxiliary_pids = auxiliary_pids;
+        self.highlight = highlight;
     }
 
     fn get_ppids(&self, pid: i32, ppids: &mut Vec<i32>) {
@@ -250,6 +386,30 @@ impl View {
         }
     }
 
+    /// PIDs of all descendants of the process
+    pub fn descendant_pids(&self, pid: i32) -> Vec<i32> {
+        let mut ret = Vec::new();
+        for p in self.ppids.keys() {
+            let mut ppids = vec![];
+            self.get_ppids(*p, &mut ppids);
+            if *p != pid && ppids.contains(&pid) {
+                ret.push(*p);
+            }
+        }
+        ret.sort_unstable();
+        ret
+    }
+
+    /// PIDs matched by the keywords except auxiliary processes and procs itself
+    pub fn matched_pids(&self) -> Vec<i32> {
+        let own_pid = std::process::id() as i32;
+        self.visible_pids
+            .iter()
+            .filter(|x| **x != own_pid && !self.auxiliary_pids.contains(x))
+            .copied()
+            .collect()
+    }
+
     pub fn adjust(&mut self, config: &Config, min_widths: &HashMap<usize, usize>) {
         for (i, ref mut c) in self.columns.iter_mut().enumerate() {
             let order = if i == self.sort_info.idx {
@@ -347,7 +507,16 @@ impl View {
             let _ = self.display_unit(&config, theme);
         }
 
-        for pid in &self.visible_pids {
+        // Only the scrolled page is shown in watch mode
+        let pids = if opt.watch_mode {
+            let begin = self.scroll.min(self.visible_pids.len());
+            let end = (begin + self.page_size()).min(self.visible_pids.len());
+            self.visible_pids[begin..end].to_vec()
+        } else {
+            self.visible_pids.clone()
+        };
+
+        for pid in &pids {
             let auxiliary = self.auxiliary_pids.contains(pid);
             let _ = self.display_content(&config, *pid, theme, auxiliary);
         }
@@ -355,6 +524,250 @@ impl View {
         Ok(())
     }
 
+    /// Number of process rows in a page of watch mode
+    ///
+    /// 5 lines are used by the header of watch mode, the column header/unit and the last line.
+    pub fn page_size(&self) -> usize {
+        self.term_info.height.saturating_sub(5).max(1)
+    }
+
+    pub fn display_json(&self) -> Result<(), Error> {
+        self.term_info
+            .write_output(&serde_json::to_string(&self.json_contents())?)?;
+        Ok(())
+    }
+
+    pub fn json_contents(&self) -> serde_json::Value {
+        let mut procs = Vec::new();
+        for pid in &self.visible_pids {
+            procs.push(self.json_content(*pid));
+        }
+        serde_json::Value::Array(procs)
+    }
+
+    pub fn display_csv(&self, opt: &Opt, config: &Config, delimiter: char) -> Result<(), Error> {
+        let columns: Vec<_> = self
+            .columns
+            .iter()
+            .filter(|c| View::export_name(&c.kind).is_some())
+            .collect();
+        let delimiter_str = delimiter.to_string();
+
+        if !opt.no_header {
+            let mut row = Vec::new();
+            for c in &columns {
+                let header = c
+                    .column
+                    .display_header(&ConfigColumnAlign::Left, None, config);
+                row.push(escape_csv(header.trim_end(), delimiter).into_owned());
+            }
+            self.term_info.write_output(&row.join(&delimiter_str))?;
+        }
+
+        for pid in &self.visible_pids {
+            let mut row = Vec::new();
+            for c in &columns {
+                let content = View::raw_content(c.column.as_ref(), *pid);
+                row.push(escape_csv(&content, delimiter).into_owned());
+            }
+            self.term_info.write_output(&row.join(&delimiter_str))?;
+        }
+        Ok(())
+    }
+
+    pub fn display_markdown(&self, config: &Config) -> Result<(), Error> {
+        let columns = self.table_columns();
+
+        let mut header = Vec::new();
+        let mut align = Vec::new();
+        for (_, c) in &columns {
+            let (name, unit) = View::table_header(c, config);
+            if unit.is_empty() {
+                header.push(escape_markdown(&name));
+            } else {
+                header.push(escape_markdown(&format!("{} {}", name, unit)));
+            }
+            align.push(match c.align {
+                ConfigColumnAlign::Left => ":--",
+                ConfigColumnAlign::Right => "--:",
+                ConfigColumnAlign::Center => ":-:",
+            });
+        }
+
+        self.term_info
+            .write_output(&format!("| {} |", header.join(" | ")))?;
+        self.term_info
+            .write_output(&format!("| {} |", align.join(" | ")))?;
+
+        for pid in &self.visible_pids {
+            let mut row = Vec::new();
+            for (_, c) in &columns {
+                row.push(escape_markdown(&View::table_content(c, *pid)));
+            }
+            self.term_info
+                .write_output(&format!("| {} |", row.join(" | ")))?;
+        }
+        Ok(())
+    }
+
+    pub fn display_html(
+        &self,
+        opt: &Opt,
+        config: &Config,
+        theme: &ConfigTheme,
+    ) -> Result<(), Error> {
+        let columns = self.table_columns();
+
+        let background = match theme {
+            ConfigTheme::Light => "#ffffff",
+            _ => "#000000",
+        };
+
+        let mut lines = Vec::new();
+        lines.push(format!(
+            "<table class=\"procs\" style=\"font-family: monospace; white-space: pre; background-color: {}\">",
+            background
+        ));
+
+        if !opt.no_header {
+            let header_color = color_to_css(&config.style.header, theme, false);
+            let unit_color = color_to_css(&config.style.unit, theme, false);
+            let mut header = String::from("");
+            let mut unit = String::from("");
+            let mut has_unit = false;
+            for (_, c) in &columns {
+                let (x, y) = View::table_header(c, config);
+                has_unit |= !y.is_empty();
+                header.push_str(&View::html_cell("th", c, &x, &header_color));
+                unit.push_str(&View::html_cell("th", c, &y, &unit_color));
+            }
+            lines.push(String::from("<thead>"));
+            lines.push(format!("<tr>{}</tr>", header));
+            if has_unit {
+                lines.push(format!("<tr>{}</tr>", unit));
+            }
+            lines.push(String::from("</thead>"));
+        }
+
+        lines.push(String::from("<tbody>"));
+        for pid in &self.visible_pids {
+            let auxiliary = self.auxiliary_pids.contains(pid);
+            let mut row = String::from("");
+            for (_, c) in &columns {
+                let content = View::table_content(c, *pid);
+                let color = select_color(&content, &c.style, &config.style);
+                let color = color_to_css(color, theme, auxiliary);
+                row.push_str(&View::html_cell("td", c, &content, &color));
+            }
+            lines.push(format!("<tr>{}</tr>", row));
+        }
+        lines.push(String::from("</tbody>"));
+        lines.push(String::from("</table>"));
+
+        for line in lines {
+            self.term_info.write_output(&line)?;
+        }
+        Ok(())
+    }
+
+    // Columns shown in table formats. Separator and Empty are replaced by table cells.
+    fn table_columns(&self) -> Vec<(usize, &ColumnInfo)> {
+        self.columns
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| {
+                c.kind != ConfigColumnKind::Separator && c.kind != ConfigColumnKind::Empty
+            })
+            .collect()
+    }
+
+    // Header and unit without the sort marker, which is decoration of the terminal
+    fn table_header(c: &ColumnInfo, config: &Config) -> (String, String) {
+        let header = c.column.display_header(&c.align, None, config);
+        let unit = c.column.display_unit(&c.align);
+        (header.trim().to_string(), unit.trim().to_string())
+    }
+
+    fn table_content(c: &ColumnInfo, pid: i32) -> String {
+        let content = c.column.display_content(pid, &c.align).unwrap_or_default();
+        // Leading spaces of the left-aligned column ( e.g. Tree ) are significant
+        match c.align {
+            ConfigColumnAlign::Left => content.trim_end().to_string(),
+            ConfigColumnAlign::Right => content.trim_start().to_string(),
+            ConfigColumnAlign::Center => content.trim().to_string(),
+        }
+    }
+
+    fn html_cell(tag: &str, c: &ColumnInfo, content: &str, color: &str) -> String {
+        let align = match c.align {
+            ConfigColumnAlign::Left => "left",
+            ConfigColumnAlign::Right => "right",
+            ConfigColumnAlign::Center => "center",
+        };
+        format!(
+            "<{} class=\"{:?}\" style=\"color: {}; text-align: {}\">{}</{}>",
+            tag,
+            c.kind,
+            color,
+            align,
+            escape_html(content),
+            tag
+        )
+    }
+
+    pub fn display_template(&self, template: &Template) -> Result<(), Error> {
+        for pid in &self.visible_pids {
+            let mut row = String::from("");
+            for item in &template.items {
+                match item {
+                    TemplateItem::Text(x) => row.push_str(x),
+                    TemplateItem::Column(kind) => {
+                        if let Some(column) = self.get_column(kind) {
+                            row.push_str(&View::raw_content(column, *pid));
+                        }
+                    }
+                }
+            }
+            self.term_info.write_output(&row)?;
+        }
+        Ok(())
+    }
+
+    fn raw_content(column: &dyn Column, pid: i32) -> String {
+        column.value(pid).map(|x| x.to_string()).unwrap_or_default()
+    }
+
+    pub fn display_prometheus(&self) -> Result<(), Error> {
+        for line in exporter::render_prometheus(self).lines() {
+            self.term_info.write_output(line)?;
+        }
+        Ok(())
+    }
+
+    fn json_content(&self, pid: i32) -> serde_json::Value {
+        let mut map = serde_json::Map::new();
+        for c in &self.columns {
+            if let Some(name) = View::export_name(&c.kind) {
+                let value = c.column.value(pid).map(|x| x.to_json());
+                map.insert(name.to_string(), value.unwrap_or(serde_json::Value::Null));
+            }
+        }
+        serde_json::Value::Object(map)
+    }
+
+    fn export_name(kind: &ConfigColumnKind) -> Option<&'static str> {
+        match kind {
+            ConfigColumnKind::Empty
+            | ConfigColumnKind::Separator
+            | ConfigColumnKind::Slot
+            | ConfigColumnKind::Tree => None,
+            // Username is an alias of User and it is not included in KIND_LIST
+            #[cfg(not(target_os = "windows"))]
+            ConfigColumnKind::Username => Some(KIND_LIST[&ConfigColumnKind::User].0),
+            x => KIND_LIST.get(x).map(|(name, _)| *name),
+        }
+    }
+
     fn display_header(&self, config: &Config, theme: &ConfigTheme) -> Result<(), Error> {
         let mut row = String::from("");
         for (i, c) in self.columns.iter().enumerate() {
@@ -412,10 +825,11 @@ impl View {
             row = format!(
                 "{} {}",
                 row,
-                apply_style(
+                self.highlight_content(
                     c.column.display_content(pid, &c.align).unwrap(),
-                    &c.style,
-                    &config.style,
+                    c,
+                    pid,
+                    config,
                     theme,
                     auxiliary
                 )
@@ -423,10 +837,133 @@ impl View {
         }
         row = row.trim_end().to_string();
         row = truncate(&row, self.term_info.width).to_string();
+        if self.selected_pid == Some(pid) {
+            row = console::style(console::strip_ansi_codes(&row))
+                .reverse()
+                .to_string();
+        }
         self.term_info.write_line(&row)?;
         Ok(())
     }
 
+    /// Apply the column style, and highlight the ranges matched with keywords
+    fn highlight_content(
+        &self,
+        content: String,
+        c: &ColumnInfo,
+        pid: i32,
+        config: &Config,
+        theme: &ConfigTheme,
+        auxiliary: bool,
+    ) -> String {
+        // Auxiliary processes are not matched with keywords
+        let mut ranges = if auxiliary {
+            Vec::new()
+        } else {
+            self.highlight_ranges(&content, c, pid)
+        };
+
+        if ranges.is_empty() {
+            return apply_style(content, &c.style, &config.style, theme, auxiliary).to_string();
+        }
+
+        let color = select_color(&content, &c.style, &config.style);
+
+        ranges.sort_unstable();
+        let mut ret = String::from("");
+        let mut pos = 0;
+        for (start, end) in ranges {
+            if end <= pos {
+                continue;
+            }
+            let start = start.max(pos);
+            if pos < start {
+                let x = content[pos..start].to_string();
+                ret.push_str(&apply_color(x, color, theme, false).to_string());
+            }
+            let x = content[start..end].to_string();
+            ret.push_str(&apply_color(x, &config.style.search_match, theme, false).to_string());
+            pos = end;
+        }
+        if pos < content.len() {
+            let x = content[pos..].to_string();
+            ret.push_str(&apply_color(x, color, theme, false).to_string());
+        }
+        ret
+    }
+
+    /// Byte ranges of the content matched with keywords
+    ///
+    /// Each element of list columns like TcpPort is matched separately,
+    /// and the whole content is matched with field-qualified and range keywords.
+    fn highlight_ranges(&self, content: &str, c: &ColumnInfo, pid: i32) -> Vec<(usize, usize)> {
+        let mut ranges = Vec::new();
+        let mut push = |start: usize, end: usize| {
+            // Padding spaces are not highlighted
+            let x = &content[start..end];
+            let start = start + (x.len() - x.trim_start().len());
+            let end = end - (x.len() - x.trim_end().len());
+            if start < end {
+                ranges.push((start, end));
+            }
+        };
+
+        let mut patterns = Vec::new();
+        if c.nonnumeric_search {
+            patterns.extend(self.highlight.nonnumeric.iter());
+        }
+        if c.numeric_search {
+            patterns.extend(self.highlight.numeric.iter());
+        }
+        let elements = if c.column.value_type() == Some("List") {
+            View::list_elements(content)
+        } else {
+            vec![(0, content.len())]
+        };
+        for p in patterns {
+            for (start, end) in &elements {
+                for m in p.find_iter(&content[*start..*end]) {
+                    push(start + m.start(), start + m.end());
+                }
+            }
+        }
+
+        let column = c.column.as_ref();
+        let query = self
+            .highlight
+            .queries
+            .iter()
+            .any(|q| q.kind == c.kind && q.matches(column, pid, self.highlight.smart_case));
+        let range = c.numeric_search
+            && self
+                .highlight
+                .ranges
+                .iter()
+                .any(|r| r.contains(column, pid));
+        if query || range {
+            push(0, content.len());
+        }
+        ranges
+    }
+
+    // Byte ranges of the elements in list content like "[80, 443]"
+    fn list_elements(content: &str) -> Vec<(usize, usize)> {
+        let mut ret = Vec::new();
+        let mut start = 0;
+        for (i, c) in content.char_indices() {
+            if c == '[' || c == ']' || c == ',' {
+                if start < i {
+                    ret.push((start, i));
+                }
+                start = i + 1;
+            }
+        }
+        if start < content.len() {
+            ret.push((start, content.len()));
+        }
+        ret
+    }
+
     fn get_sort_info(opt: &Opt, config: &Config, cols: &[ColumnInfo]) -> SortInfo {
         let (mut sort_idx, sort_order) = match (&opt.sorta, &opt.sortd) {
             (Some(sort), _) | (_, Some(sort)) => {
@@ -459,31 +996,317 @@ impl View {
         }
     }
 
-    fn search<T: AsRef<str>>(
+    fn search(
         pid: i32,
-        keyword_numeric: &[T],
-        keyword_nonnumeric: &[T],
+        keyword: &SearchKeyword,
         cols_numeric: &[&dyn Column],
         cols_nonnumeric: &[&dyn Column],
-        config: &Config,
+        search: &ConfigSearch,
         logic: &ConfigSearchLogic,
     ) -> bool {
-        let ret_nonnumeric = match config.search.nonnumeric_search {
-            ConfigSearchKind::Partial => {
-                find_partial(cols_nonnumeric, pid, keyword_nonnumeric, logic, config.search.smart_case)
+        let ret_nonnumeric = match search.nonnumeric_search {
+            ConfigSearchKind::Partial => find_partial(
+                cols_nonnumeric,
+                pid,
+                &keyword.nonnumeric,
+                logic,
+                search.smart_case,
+            ),
+            ConfigSearchKind::Exact => find_exact(cols_nonnumeric, pid, &keyword.nonnumeric, logic),
+            ConfigSearchKind::Regex => {
+                find_regex(cols_nonnumeric, pid, &keyword.regex_nonnumeric, logic)
             }
-            ConfigSearchKind::Exact => find_exact(cols_nonnumeric, pid, keyword_nonnumeric, logic),
         };
-        let ret_numeric = match config.search.numeric_search {
-            ConfigSearchKind::Partial => find_partial(cols_numeric, pid, keyword_numeric, logic, false),
-            ConfigSearchKind::Exact => find_exact(cols_numeric, pid, keyword_numeric, logic),
+        let ret_numeric = match search.numeric_search {
+            ConfigSearchKind::Partial => {
+                find_partial(cols_numeric, pid, &keyword.numeric, logic, false)
+            }
+            ConfigSearchKind::Exact => find_exact(cols_numeric, pid, &keyword.numeric, logic),
+            ConfigSearchKind::Regex => find_regex(cols_numeric, pid, &keyword.regex_numeric, logic),
         };
+        let ret_range = find_range(cols_numeric, pid, &keyword.ranges, logic);
+        let ret_query = find_query(&keyword.queries, pid, logic, search.smart_case);
         match logic {
-            ConfigSearchLogic::And => ret_nonnumeric & ret_numeric,
-            ConfigSearchLogic::Or => ret_nonnumeric | ret_numeric,
-            ConfigSearchLogic::Nand => !(ret_nonnumeric & ret_numeric),
-            ConfigSearchLogic::Nor => !(ret_nonnumeric | ret_numeric),
+            ConfigSearchLogic::And => ret_nonnumeric & ret_numeric & ret_range & ret_query,
+            ConfigSearchLogic::Or => ret_nonnumeric | ret_numeric | ret_range | ret_query,
+            ConfigSearchLogic::Nand => !(ret_nonnumeric & ret_numeric & ret_range & ret_query),
+            ConfigSearchLogic::Nor => !(ret_nonnumeric | ret_numeric | ret_range | ret_query),
+        }
+    }
+
+    /// Check that keywords searched as regular expression can be compiled
+    pub fn check_regex(opt: &Opt, config: &Config) -> Result<(), Error> {
+        let search = View::search_config(opt, config);
+        let (include, exclude) = View::split_keywords(opt);
+        for k in include.iter().chain(exclude.iter()) {
+            if Query::parse(k).is_some() || NumericRange::parse(k).is_some() {
+                continue;
+            }
+            let kind = match classify(k) {
+                KeywordClass::Numeric => &search.numeric_search,
+                KeywordClass::NonNumeric => &search.nonnumeric_search,
+            };
+            if let ConfigSearchKind::Regex = kind {
+                compile_regex(k, search.smart_case)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the filters specified by command-line options except keywords
+    fn match_filter(&self, opt: &Opt, pid: i32) -> bool {
+        if opt.older_than.is_some() || opt.newer_than.is_some() {
+            let age = self
+                .get_column(&ConfigColumnKind::ElapsedTime)
+                .and_then(|c| c.value(pid))
+                .and_then(|x| x.as_f64());
+            let age = match age {
+                Some(x) => Duration::from_secs_f64(x),
+                None => return false,
+            };
+            if matches!(opt.older_than, Some(x) if age < x) {
+                return false;
+            }
+            if matches!(opt.newer_than, Some(x) if age >= x) {
+                return false;
+            }
+        }
+        if !opt.user.is_empty()
+            && !self.match_value(USER_KINDS, pid, |x| opt.user.iter().any(|u| u == x))
+        {
+            return false;
+        }
+        if !opt.uid.is_empty()
+            && !self.match_value(UID_KINDS, pid, |x| {
+                opt.uid.iter().any(|u| u.to_string() == x)
+            })
+        {
+            return false;
         }
+        if opt.mine && !self.match_mine(pid) {
+            return false;
+        }
+        if !opt.descendants_of.is_empty() {
+            let mut ppids = vec![];
+            self.get_ppids(pid, &mut ppids);
+            if !opt
+                .descendants_of
+                .iter()
+                .any(|x| *x == pid || ppids.contains(x))
+            {
+                return false;
+            }
+        }
+        if !opt.ancestors_of.is_empty() {
+            let hit = opt.ancestors_of.iter().any(|x| {
+                let mut ppids = vec![];
+                self.get_ppids(*x, &mut ppids);
+                *x == pid || ppids.contains(&pid)
+            });
+            if !hit {
+                return false;
+            }
+        }
+        if !opt.port.is_empty() && !opt.port.iter().any(|x| self.match_port(x, pid)) {
+            return false;
+        }
+        if !opt.file.is_empty() || !opt.file_prefix.is_empty() {
+            let files = match self.open_files.get(&pid) {
+                Some(x) => x,
+                None => return false,
+            };
+            let hit = files.iter().any(|f| {
+                opt.file.iter().any(|x| f == x) || opt.file_prefix.iter().any(|x| f.starts_with(x))
+            });
+            if !hit {
+                return false;
+            }
+        }
+        true
+    }
+
+    #[cfg(target_os = "linux")]
+    fn collect_open_files(proc: &[ProcessInfo]) -> Result<HashMap<i32, Vec<PathBuf>>, Error> {
+        let mut ret = HashMap::new();
+        for p in proc {
+            let mut files = Vec::new();
+            if let Ok(fds) = p.curr_proc.fd() {
+                for fd in fds {
+                    if let FDTarget::Path(x) = fd.target {
+                        files.push(x);
+                    }
+                }
+            }
+            ret.insert(p.pid, files);
+        }
+        Ok(ret)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn collect_open_files(_proc: &[ProcessInfo]) -> Result<HashMap<i32, Vec<PathBuf>>, Error> {
+        bail!("--file and --file-prefix are supported on Linux only");
+    }
+
+    /// Check whether the value of any column matches
+    fn match_value<F: Fn(&str) -> bool>(&self, kinds: &[ConfigColumnKind], pid: i32, f: F) -> bool {
+        kinds.iter().any(|kind| {
+            let value = self.get_column(kind).and_then(|c| c.value(pid));
+            matches!(value, Some(x) if f(&x.to_string()))
+        })
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn match_mine(&self, pid: i32) -> bool {
+        let uid = users::get_current_uid().to_string();
+        self.match_value(UID_KINDS, pid, |x| x == uid)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn match_port(&self, spec: &PortSpec, pid: i32) -> bool {
+        let mut kinds = Vec::new();
+        if spec.tcp {
+            kinds.push(ConfigColumnKind::TcpPort);
+        }
+        if spec.udp {
+            kinds.push(ConfigColumnKind::UdpPort);
+        }
+        let port = spec.port.to_string();
+        self.match_value(&kinds, pid, |x| x.split(',').any(|x| x == port))
+    }
+
+    #[cfg(target_os = "windows")]
+    fn match_port(&self, _spec: &PortSpec, _pid: i32) -> bool {
+        false
+    }
+
+    #[cfg(target_os = "windows")]
+    fn match_mine(&self, pid: i32) -> bool {
+        let user = std::env::var("USERNAME").unwrap_or_default();
+        self.match_value(USER_KINDS, pid, |x| x.eq_ignore_ascii_case(&user))
+    }
+
+    /// Split keywords into included and excluded ones
+    ///
+    /// `!keyword` and `--exclude keyword` are excluded.
+    fn split_keywords(opt: &Opt) -> (Vec<&str>, Vec<&str>) {
+        let mut include = Vec::new();
+        let mut exclude: Vec<&str> = opt.exclude.iter().map(|x| x.as_str()).collect();
+        for k in &opt.keyword {
+            match k.strip_prefix('!') {
+                Some(x) if !x.is_empty() => exclude.push(x),
+                _ => include.push(k.as_str()),
+            }
+        }
+        (include, exclude)
+    }
+
+    /// Classify keywords into field-qualified queries, numeric and non-numeric keywords
+    fn search_keyword<'a>(
+        &'a self,
+        keyword: &[&'a str],
+        search: &ConfigSearch,
+    ) -> SearchKeyword<'a> {
+        let mut ret = SearchKeyword {
+            numeric: Vec::new(),
+            nonnumeric: Vec::new(),
+            regex_numeric: Vec::new(),
+            regex_nonnumeric: Vec::new(),
+            ranges: Vec::new(),
+            queries: Vec::new(),
+        };
+
+        for k in keyword {
+            if let Some(query) = Query::parse(k) {
+                let column = self.get_column(&query.kind);
+                ret.queries.push((query, column));
+                continue;
+            }
+            if let Some(range) = NumericRange::parse(k) {
+                ret.ranges.push(range);
+                continue;
+            }
+            match classify(k) {
+                KeywordClass::Numeric => ret.numeric.push(*k),
+                KeywordClass::NonNumeric => ret.nonnumeric.push(*k),
+            }
+        }
+
+        // Keywords are validated in `View::new`, so invalid patterns never happen here
+        let compile = |keyword: &[&str], kind: &ConfigSearchKind| -> Vec<Regex> {
+            if let ConfigSearchKind::Regex = kind {
+                keyword
+                    .iter()
+                    .filter_map(|k| compile_regex(k, search.smart_case).ok())
+                    .collect()
+            } else {
+                Vec::new()
+            }
+        };
+        ret.regex_numeric = compile(&ret.numeric, &search.numeric_search);
+        ret.regex_nonnumeric = compile(&ret.nonnumeric, &search.nonnumeric_search);
+        ret
+    }
+
+    /// Generate patterns to highlight the keywords as the same as search
+    fn highlight_pattern(keyword: &SearchKeyword, search: &ConfigSearch) -> Highlight {
+        let pattern = |keyword: &[&str], regex: &[Regex], kind: &ConfigSearchKind, smart_case| {
+            let mut ret = Vec::new();
+            for k in keyword {
+                let x = match kind {
+                    ConfigSearchKind::Partial => compile_regex(&regex::escape(k), smart_case).ok(),
+                    ConfigSearchKind::Exact => {
+                        Regex::new(&format!(r"^\s*{}\s*$", regex::escape(k))).ok()
+                    }
+                    ConfigSearchKind::Regex => None,
+                };
+                ret.extend(x);
+            }
+            ret.extend(regex.iter().cloned());
+            ret
+        };
+
+        Highlight {
+            numeric: pattern(
+                &keyword.numeric,
+                &keyword.regex_numeric,
+                &search.numeric_search,
+                false,
+            ),
+            nonnumeric: pattern(
+                &keyword.nonnumeric,
+                &keyword.regex_nonnumeric,
+                &search.nonnumeric_search,
+                search.smart_case,
+            ),
+            ranges: keyword.ranges.clone(),
+            queries: keyword.queries.iter().map(|(q, _)| q.clone()).collect(),
+            smart_case: search.smart_case,
+        }
+    }
+
+    /// Logical operation of search keywords overridden by command-line options
+    pub fn search_logic(opt: &Opt, config: &Config) -> ConfigSearchLogic {
+        if opt.and {
+            ConfigSearchLogic::And
+        } else if opt.or {
+            ConfigSearchLogic::Or
+        } else if opt.nand {
+            ConfigSearchLogic::Nand
+        } else if opt.nor {
+            ConfigSearchLogic::Nor
+        } else {
+            config.search.logic.clone()
+        }
+    }
+
+    /// Search setting overridden by command-line options
+    fn search_config(opt: &Opt, config: &Config) -> ConfigSearch {
+        let mut search = config.search.clone();
+        if opt.regex {
+            search.numeric_search = ConfigSearchKind::Regex;
+            search.nonnumeric_search = ConfigSearchKind::Regex;
+        }
+        search
     }
 
     #[cfg(not(any(target_os = "windows", target_os = "linux")))]
@@ -544,3 +1367,72 @@ impl View {
         current
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use structopt::StructOpt;
+
+    fn filtered_view(keyword: &str, config: &Config) -> View {
+        let opt = Opt::from_iter(["procs", keyword].iter());
+        let mut view = View::new(&opt, config, false).unwrap();
+        view.filter(&opt, config);
+        view.adjust(config, &HashMap::new());
+        view
+    }
+
+    #[test]
+    fn test_highlight_content() {
+        let mut config: Config = toml::from_str(CONFIG_DEFAULT).unwrap();
+        config.search.numeric_search = ConfigSearchKind::Exact;
+        let pid = std::process::id() as i32;
+
+        let keywords = [
+            pid.to_string(),
+            format!("pid={}", pid),
+            String::from(">0"),
+            String::from("<0"),
+        ];
+        for keyword in keywords.iter() {
+            let view = filtered_view(keyword, &config);
+            let c = view
+                .columns
+                .iter()
+                .find(|c| c.kind == ConfigColumnKind::Pid)
+                .unwrap();
+            let content = c.column.display_content(pid, &c.align).unwrap();
+            let start = content.find(&pid.to_string()).unwrap();
+            let end = start + pid.to_string().len();
+            if keyword == "<0" {
+                assert!(view.highlight_ranges(&content, c, pid).is_empty());
+            } else {
+                assert_eq!(view.highlight_ranges(&content, c, pid), vec![(start, end)]);
+            }
+
+            let ret =
+                view.highlight_content(content.clone(), c, pid, &config, &ConfigTheme::Dark, false);
+            assert_eq!(console::strip_ansi_codes(&ret), content);
+            let ret =
+                view.highlight_content(content.clone(), c, pid, &config, &ConfigTheme::Dark, true);
+            assert_eq!(console::strip_ansi_codes(&ret), content);
+        }
+
+        // Each element of list columns is matched with exact keywords
+        #[cfg(not(target_os = "windows"))]
+        {
+            let view = filtered_view("443", &config);
+            let c = ColumnInfo {
+                column: Box::new(TcpPort::new(None)),
+                kind: ConfigColumnKind::TcpPort,
+                style: ConfigColumnStyle::ByUnit,
+                nonnumeric_search: false,
+                numeric_search: true,
+                align: ConfigColumnAlign::Left,
+                max_width: None,
+                min_width: None,
+            };
+            assert_eq!(view.highlight_ranges("[80, 443] ", &c, pid), vec![(5, 8)]);
+            assert!(view.highlight_ranges("[4430]", &c, pid).is_empty());
+        }
+    }
+}
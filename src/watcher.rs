@@ -3,7 +3,7 @@ use crate::term_info::TermInfo;
 use crate::util::get_theme;
 use crate::view::View;
 use crate::Opt;
-use anyhow::Error;
+use anyhow::{bail, Error};
 use chrono::offset::Local;
 use getch::Getch;
 use std::collections::HashMap;
@@ -14,50 +14,152 @@ use std::time::Duration;
 enum Command {
     Wake,
     Sleep,
-    Next,
-    Prev,
-    Ascending,
-    Descending,
+    Key(Key),
     Quit,
 }
 
+enum Key {
+    Char(char),
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+    Escape,
+    Enter,
+    Backspace,
+    Tab,
+}
+
+enum Mode {
+    Normal,
+    Signal(i32),
+    Confirm(usize, i32),
+    IoClass(i32),
+    IoLevel(usize, i32),
+    Search(String),
+}
+
+const SIGNALS: &[&str] = &["TERM", "KILL", "HUP", "INT", "STOP", "CONT", "USR1", "USR2"];
+
+const IO_CLASSES: &[&str] = &["realtime", "best-effort", "idle"];
+
 pub struct Watcher;
 
 #[cfg_attr(tarpaulin, skip)]
 impl Watcher {
     fn spawn_cmd(tx: Sender<Command>) {
         let _ = thread::spawn(move || {
-            let getch = Getch::new();
-            loop {
-                match getch.getch() {
-                    Ok(x) if char::from(x) == 'q' => {
-                        let _ = tx.send(Command::Quit);
-                        break;
-                    }
-                    Ok(x) if char::from(x) == 'n' => {
-                        let _ = tx.send(Command::Next);
-                    }
-                    Ok(x) if char::from(x) == 'p' => {
-                        let _ = tx.send(Command::Prev);
-                    }
-                    Ok(x) if char::from(x) == 'a' => {
-                        let _ = tx.send(Command::Ascending);
-                    }
-                    Ok(x) if char::from(x) == 'd' => {
-                        let _ = tx.send(Command::Descending);
-                    }
-                    // On windows, _getch return EXT(0x3) by Ctrl-C
-                    #[cfg(target_os = "windows")]
-                    Ok(x) if x == 3 => {
-                        let _ = tx.send(Command::Quit);
-                        break;
-                    }
-                    _ => (),
+            while let Some(key) = Watcher::read_key() {
+                if tx.send(Command::Key(key)).is_err() {
+                    break;
                 }
             }
         });
     }
 
+    #[cfg(not(target_os = "windows"))]
+    fn read_byte(timeout_ms: Option<i32>) -> Option<u8> {
+        // stdin is read without buffer to check the rest of escape sequence by poll
+        unsafe {
+            if let Some(timeout_ms) = timeout_ms {
+                let mut fds = libc::pollfd {
+                    fd: libc::STDIN_FILENO,
+                    events: libc::POLLIN,
+                    revents: 0,
+                };
+                if libc::poll(&mut fds, 1, timeout_ms) <= 0 {
+                    return None;
+                }
+            }
+            let mut buf = 0u8;
+            let ret = libc::read(
+                libc::STDIN_FILENO,
+                &mut buf as *mut u8 as *mut libc::c_void,
+                1,
+            );
+            if ret == 1 {
+                Some(buf)
+            } else {
+                None
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn read_key() -> Option<Key> {
+        loop {
+            let key = match Watcher::read_byte(None)? {
+                0x1b => match Watcher::read_byte(Some(50)) {
+                    None => Some(Key::Escape),
+                    Some(b'[') | Some(b'O') => match Watcher::read_byte(Some(50)) {
+                        Some(b'A') => Some(Key::Up),
+                        Some(b'B') => Some(Key::Down),
+                        Some(b'H') => Some(Key::Home),
+                        Some(b'F') => Some(Key::End),
+                        // Sequences like "ESC [ 5 ~"
+                        Some(x) if x.is_ascii_digit() => match (x, Watcher::read_byte(Some(50))) {
+                            (b'5', Some(b'~')) => Some(Key::PageUp),
+                            (b'6', Some(b'~')) => Some(Key::PageDown),
+                            (b'1', Some(b'~')) | (b'7', Some(b'~')) => Some(Key::Home),
+                            (b'4', Some(b'~')) | (b'8', Some(b'~')) => Some(Key::End),
+                            _ => None,
+                        },
+                        _ => None,
+                    },
+                    _ => None,
+                },
+                b'\r' | b'\n' => Some(Key::Enter),
+                0x7f | 0x08 => Some(Key::Backspace),
+                b'\t' => Some(Key::Tab),
+                x if x < 0x80 => Some(Key::Char(char::from(x))),
+                x => {
+                    // Multi-byte UTF-8 character
+                    let len = x.leading_ones() as usize;
+                    let mut buf = vec![x];
+                    for _ in 1..len {
+                        buf.push(Watcher::read_byte(Some(50))?);
+                    }
+                    std::str::from_utf8(&buf)
+                        .ok()
+                        .and_then(|x| x.chars().next())
+                        .map(Key::Char)
+                }
+            };
+            if key.is_some() {
+                return key;
+            }
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    fn read_key() -> Option<Key> {
+        let getch = Getch::new();
+        loop {
+            let key = match getch.getch().ok()? {
+                // Arrow keys and so on are prefixed by 0xe0, or 0x00 on numpad
+                0xe0 | 0x00 => match getch.getch().ok()? {
+                    72 => Some(Key::Up),
+                    80 => Some(Key::Down),
+                    73 => Some(Key::PageUp),
+                    81 => Some(Key::PageDown),
+                    71 => Some(Key::Home),
+                    79 => Some(Key::End),
+                    _ => None,
+                },
+                0x1b => Some(Key::Escape),
+                b'\r' => Some(Key::Enter),
+                0x08 => Some(Key::Backspace),
+                b'\t' => Some(Key::Tab),
+                x => Some(Key::Char(char::from(x))),
+            };
+            if key.is_some() {
+                return key;
+            }
+        }
+    }
+
     fn spawn_sleep(rx: Receiver<Command>, tx: Sender<Command>, interval: u64) {
         let _ = thread::spawn(move || loop {
             if let Ok(Command::Quit) = rx.recv() {
@@ -68,16 +170,21 @@ impl Watcher {
         });
     }
 
-    fn display_header(term_info: &mut TermInfo, opt: &Opt, interval: u64) -> Result<(), Error> {
+    fn display_header(
+        term_info: &mut TermInfo,
+        opt: &Opt,
+        interval: u64,
+        status: &str,
+    ) -> Result<(), Error> {
         let header = if opt.tree {
             format!(
-                " Interval: {}ms, Last Updated: {} ( Quit: q or Ctrl-C )",
+                " Interval: {}ms, Last Updated: {} ( Select: Up/Down/PgUp/PgDn/Home/End, Signal: k, Nice: +/-, I/O: i, Search: /, Quit: q or Ctrl-C )",
                 interval,
                 Local::now().format("%Y/%m/%d %H:%M:%S"),
             )
         } else {
             format!(
-                " Interval: {}ms, Last Updated: {} ( Next: n, Prev: p, Ascending: a, Descending: d, Quit: q or Ctrl-C )",
+                " Interval: {}ms, Last Updated: {} ( Next: n, Prev: p, Ascending: a, Descending: d, Select: Up/Down/PgUp/PgDn/Home/End, Signal: k, Nice: +/-, I/O: i, Search: /, Quit: q or Ctrl-C )",
                 interval,
                 Local::now().format("%Y/%m/%d %H:%M:%S"),
             )
@@ -87,13 +194,264 @@ impl Watcher {
             console::style(header).white().bold().underlined()
         ))?;
 
-        term_info.write_line("")?;
+        term_info.write_line(&format!("{}", console::style(status).bold()))?;
+        Ok(())
+    }
+
+    fn status(view: &View, opt: &Opt, config: &Config, mode: &Mode, message: &str) -> String {
+        match mode {
+            Mode::Normal => String::from(message),
+            Mode::Signal(pid) => {
+                let signals: Vec<_> = SIGNALS
+                    .iter()
+                    .enumerate()
+                    .map(|(i, x)| format!("{}: {}", i + 1, x))
+                    .collect();
+                format!(
+                    " Send signal to {} ( {}, Cancel: Esc or q )",
+                    pid,
+                    signals.join(", ")
+                )
+            }
+            Mode::Confirm(i, pid) => {
+                let descendants = if opt.tree {
+                    format!(" and {} descendants", view.descendant_pids(*pid).len())
+                } else {
+                    String::from("")
+                };
+                format!(
+                    " Send SIG{} to {}{}? ( y/N )",
+                    SIGNALS[*i], pid, descendants
+                )
+            }
+            Mode::IoClass(pid) => {
+                let classes: Vec<_> = IO_CLASSES
+                    .iter()
+                    .enumerate()
+                    .map(|(i, x)| format!("{}: {}", i + 1, x))
+                    .collect();
+                format!(
+                    " Set I/O priority class of {} ( {}, Cancel: Esc or q )",
+                    pid,
+                    classes.join(", ")
+                )
+            }
+            Mode::IoLevel(i, pid) => format!(
+                " Set I/O priority level of {} in {} ( 0: highest - 7: lowest, Cancel: Esc or q )",
+                pid, IO_CLASSES[*i]
+            ),
+            Mode::Search(x) => {
+                let logic = match View::search_logic(opt, config) {
+                    ConfigSearchLogic::And => "AND",
+                    ConfigSearchLogic::Or => "OR",
+                    ConfigSearchLogic::Nand => "NAND",
+                    ConfigSearchLogic::Nor => "NOR",
+                };
+                format!(
+                    " Search: {}_ ( Logic: {} ( Tab ), Apply: Enter, Cancel: Esc ){}",
+                    x, logic, message
+                )
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn send_signal(view: &View, opt: &Opt, pid: i32, signal: usize) -> String {
+        let sig = match SIGNALS[signal] {
+            "TERM" => libc::SIGTERM,
+            "KILL" => libc::SIGKILL,
+            "HUP" => libc::SIGHUP,
+            "INT" => libc::SIGINT,
+            "STOP" => libc::SIGSTOP,
+            "CONT" => libc::SIGCONT,
+            "USR1" => libc::SIGUSR1,
+            "USR2" => libc::SIGUSR2,
+            _ => unreachable!(),
+        };
+
+        // The whole subtree is the target in tree mode
+        let mut pids = vec![pid];
+        if opt.tree {
+            pids.append(&mut view.descendant_pids(pid));
+        }
+
+        let mut errors = Vec::new();
+        for p in &pids {
+            if unsafe { libc::kill(*p, sig) } != 0 {
+                errors.push(format!("{}: {}", p, std::io::Error::last_os_error()));
+            }
+        }
+        if errors.is_empty() {
+            format!(" Sent SIG{} to {:?}", SIGNALS[signal], pids)
+        } else {
+            format!(
+                " Failed to send SIG{} ( {} )",
+                SIGNALS[signal],
+                errors.join(", ")
+            )
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    fn send_signal(_view: &View, _opt: &Opt, _pid: i32, _signal: usize) -> String {
+        String::from(" Sending signal is not supported on Windows")
+    }
+
+    #[cfg(target_os = "linux")]
+    fn get_nice(pid: i32) -> Option<i32> {
+        let proc = procfs::process::Process::new(pid).ok()?;
+        Some(proc.stat.nice as i32)
+    }
+
+    #[cfg(target_os = "macos")]
+    fn get_nice(pid: i32) -> Option<i32> {
+        // getpriority can return -1 as a valid value, so errno is checked instead
+        errno::set_errno(errno::Errno(0));
+        let nice = unsafe { libc::getpriority(libc::PRIO_PROCESS, pid as libc::id_t) };
+        if errno::errno().0 == 0 {
+            Some(nice)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn renice(pid: i32, inc: i32) -> String {
+        let nice = match Watcher::get_nice(pid) {
+            Some(x) => (x + inc).clamp(-20, 19),
+            None => return format!(" Failed to get nice value of {}", pid),
+        };
+        if unsafe { libc::setpriority(libc::PRIO_PROCESS as _, pid as libc::id_t, nice) } != 0 {
+            format!(
+                " Failed to set nice value of {} ( {} )",
+                pid,
+                std::io::Error::last_os_error()
+            )
+        } else {
+            format!(" Set nice value of {} to {}", pid, nice)
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    fn renice(_pid: i32, _inc: i32) -> String {
+        String::from(" Changing nice value is not supported on Windows")
+    }
+
+    #[cfg(target_os = "linux")]
+    fn set_ioprio(pid: i32, class: usize, level: usize) -> String {
+        // IOPRIO_WHO_PROCESS and the class values are defined in linux/ioprio.h
+        let ioprio = ((class as libc::c_int + 1) << 13) | level as libc::c_int;
+        let ret = unsafe { libc::syscall(libc::SYS_ioprio_set, 1, pid, ioprio) };
+        if ret != 0 {
+            format!(
+                " Failed to set I/O priority of {} ( {} )",
+                pid,
+                std::io::Error::last_os_error()
+            )
+        } else if IO_CLASSES[class] == "idle" {
+            format!(" Set I/O priority of {} to {}", pid, IO_CLASSES[class])
+        } else {
+            format!(
+                " Set I/O priority of {} to {}/{}",
+                pid, IO_CLASSES[class], level
+            )
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn set_ioprio(_pid: i32, _class: usize, _level: usize) -> String {
+        String::from(" Changing I/O priority is only supported on Linux")
+    }
+
+    /// Apply the keywords edited by the search prompt
+    ///
+    /// The keywords are kept if the new ones are invalid.
+    /// Run an action on `pid` only if it is still shown
+    ///
+    /// The target is fixed when the action starts, and it may be terminated before the action is applied.
+    fn with_target(view: &View, pid: i32, action: impl FnOnce() -> String) -> String {
+        if view.visible_pids.contains(&pid) {
+            action()
+        } else {
+            format!(" Process {} is not found", pid)
+        }
+    }
+
+    fn set_keyword(opt: &mut Opt, config: &Config, keyword: &str) -> String {
+        let mut new_opt = opt.clone();
+        new_opt.keyword = keyword.split_whitespace().map(String::from).collect();
+        match View::check_regex(&new_opt, config) {
+            Ok(_) => {
+                *opt = new_opt;
+                String::from("")
+            }
+            Err(x) => format!(" {}", x),
+        }
+    }
+
+    fn set_logic(opt: &mut Opt, logic: &ConfigSearchLogic) {
+        opt.and = matches!(logic, ConfigSearchLogic::And);
+        opt.or = matches!(logic, ConfigSearchLogic::Or);
+        opt.nand = matches!(logic, ConfigSearchLogic::Nand);
+        opt.nor = matches!(logic, ConfigSearchLogic::Nor);
+    }
+
+    /// Move the selection in the visible processes by `delta` rows
+    ///
+    /// If no process is selected, the selection starts from the top of the page.
+    fn move_selection(view: &View, selected: Option<i32>, delta: isize) -> Option<i32> {
+        let pids = &view.visible_pids;
+        let pos = selected.and_then(|x| pids.iter().position(|y| *y == x));
+        let pos = match pos {
+            Some(x) => (x as isize).saturating_add(delta),
+            None if delta == 1 || delta == -1 => view.scroll as isize,
+            None => (view.scroll as isize).saturating_add(delta),
+        };
+        let pos = (pos.max(0) as usize).min(pids.len().saturating_sub(1));
+        pids.get(pos).copied()
+    }
+
+    fn start_stream(opt: &Opt, config: &Config, interval: u64) -> Result<(), Error> {
+        loop {
+            let mut view = View::new(opt, config, false)?;
+            view.filter(opt, config);
+
+            let record = serde_json::json!({
+                "timestamp": Local::now().to_rfc3339(),
+                "interval_ms": interval,
+                "processes": view.json_contents(),
+            });
+
+            // Stop streaming if the reader is closed
+            if view
+                .term_info
+                .write_line(&serde_json::to_string(&record)?)
+                .is_err()
+            {
+                break;
+            }
+
+            thread::sleep(Duration::from_millis(interval));
+        }
         Ok(())
     }
 
     pub fn start(opt: &Opt, config: &Config, interval: u64) -> Result<(), Error> {
+        match opt.format.as_deref() {
+            Some("json") => return Watcher::start_stream(opt, config, interval),
+            Some(x) => bail!("format \"{}\" is not supported in watch mode", x),
+            None => (),
+        }
+
         let theme = get_theme(opt, config);
 
+        // Keywords and logic can be changed by the search prompt
+        let mut opt = opt.clone();
+        let mut prev_search = (opt.keyword.clone(), View::search_logic(&opt, config));
+
+        // The terminal setting is restored when this is dropped
+        let _getch = Getch::new();
+
         let (tx_cmd, rx_cmd) = channel();
         Watcher::spawn_cmd(tx_cmd.clone());
 
@@ -108,8 +466,18 @@ impl Watcher {
         let mut min_widths = HashMap::new();
         let mut prev_term_width = 0;
         let mut prev_term_height = 0;
+        let mut selected = None;
+        let mut scroll = 0;
+        let mut mode = Mode::Normal;
+        let mut message = String::from("");
+        let mut view = View::new(&opt, config, true)?;
+        let mut refresh = false;
+        let mut sleeping = false;
         'outer: loop {
-            let mut view = View::new(opt, config, true)?;
+            // Processes are collected at each interval, and the other keys only update the view
+            if refresh {
+                view = View::new(&opt, config, true)?;
+            }
 
             // Override sort_info by key
             if !opt.tree {
@@ -117,25 +485,50 @@ impl Watcher {
                 view.sort_info.order = sort_order.clone().unwrap_or(view.sort_info.order);
             }
 
-            view.filter(opt, config);
+            view.filter(&opt, config);
             view.adjust(config, &min_widths);
             for (i, c) in view.columns.iter().enumerate() {
                 min_widths.insert(i, c.column.get_width());
             }
 
+            // Keep the selected process, or clear the selection if it is terminated
+            let selected_pos =
+                selected.and_then(|x| view.visible_pids.iter().position(|y| *y == x));
+            if selected_pos.is_none() {
+                selected = None;
+            }
+            view.selected_pid = selected;
+
+            // Scroll to show the selected process
+            let page = view.page_size();
+            if let Some(selected_pos) = selected_pos {
+                if selected_pos < scroll {
+                    scroll = selected_pos;
+                } else if selected_pos >= scroll + page {
+                    scroll = selected_pos + 1 - page;
+                }
+            }
+            scroll = scroll.min(view.visible_pids.len().saturating_sub(page));
+            view.scroll = scroll;
+
             let resized = prev_term_width != view.term_info.width
                 || prev_term_height != view.term_info.height;
             if resized {
                 term_info.clear_screen()?;
             }
-            Watcher::display_header(&mut view.term_info, opt, interval)?;
+            let status = Watcher::status(&view, &opt, config, &mode, &message);
+            Watcher::display_header(&mut view.term_info, &opt, interval, &status)?;
 
-            view.display(opt, config, &theme)?;
+            view.display(&opt, config, &theme)?;
 
             view.term_info.clear_rest_lines()?;
             view.term_info.move_cursor_to(0, 0)?;
 
-            tx_sleep.send(Command::Sleep)?;
+            // Only one sleep is requested at once to keep the interval
+            if !sleeping {
+                tx_sleep.send(Command::Sleep)?;
+                sleeping = true;
+            }
             let mut cmds = Vec::new();
             if let Ok(cmd) = rx_cmd.recv() {
                 cmds.push(cmd);
@@ -144,18 +537,172 @@ impl Watcher {
                 }
             }
 
+            refresh = false;
             for cmd in cmds {
-                match cmd {
-                    Command::Quit => {
+                let key = match cmd {
+                    Command::Wake => {
+                        sleeping = false;
+                        refresh = true;
+                        continue;
+                    }
+                    Command::Key(x) => x,
+                    _ => continue,
+                };
+                let query_kinds = View::query_kinds(&opt);
+                mode = match (mode, key) {
+                    (Mode::Normal, Key::Char('q')) | (_, Key::Char('\u{3}')) => {
                         tx_sleep.send(Command::Quit)?;
                         view.term_info.clear_screen()?;
                         break 'outer;
                     }
-                    Command::Next => sort_idx = Some(view.inc_sort_column()),
-                    Command::Prev => sort_idx = Some(view.dec_sort_column()),
-                    Command::Ascending => sort_order = Some(ConfigSortOrder::Ascending),
-                    Command::Descending => sort_order = Some(ConfigSortOrder::Descending),
-                    _ => (),
+                    (Mode::Normal, Key::Char('n')) => {
+                        sort_idx = Some(view.inc_sort_column());
+                        Mode::Normal
+                    }
+                    (Mode::Normal, Key::Char('p')) => {
+                        sort_idx = Some(view.dec_sort_column());
+                        Mode::Normal
+                    }
+                    (Mode::Normal, Key::Char('a')) => {
+                        sort_order = Some(ConfigSortOrder::Ascending);
+                        Mode::Normal
+                    }
+                    (Mode::Normal, Key::Char('d')) => {
+                        sort_order = Some(ConfigSortOrder::Descending);
+                        Mode::Normal
+                    }
+                    (Mode::Normal, Key::Up) => {
+                        selected = Watcher::move_selection(&view, selected, -1);
+                        Mode::Normal
+                    }
+                    (Mode::Normal, Key::Down) => {
+                        selected = Watcher::move_selection(&view, selected, 1);
+                        Mode::Normal
+                    }
+                    (Mode::Normal, Key::PageUp) => {
+                        selected = Watcher::move_selection(&view, selected, -(page as isize));
+                        Mode::Normal
+                    }
+                    (Mode::Normal, Key::PageDown) => {
+                        selected = Watcher::move_selection(&view, selected, page as isize);
+                        Mode::Normal
+                    }
+                    (Mode::Normal, Key::Home) => {
+                        selected = Watcher::move_selection(&view, selected, isize::MIN);
+                        Mode::Normal
+                    }
+                    (Mode::Normal, Key::End) => {
+                        selected = Watcher::move_selection(&view, selected, isize::MAX);
+                        Mode::Normal
+                    }
+                    (Mode::Normal, Key::Escape) => {
+                        selected = None;
+                        Mode::Normal
+                    }
+                    (Mode::Normal, Key::Char('k')) => match selected {
+                        Some(pid) => {
+                            message = String::from("");
+                            Mode::Signal(pid)
+                        }
+                        None => Mode::Normal,
+                    },
+                    (Mode::Normal, Key::Char(x)) if x == '+' || x == '-' => {
+                        if let Some(pid) = selected {
+                            let inc = if x == '+' { 1 } else { -1 };
+                            message =
+                                Watcher::with_target(&view, pid, || Watcher::renice(pid, inc));
+                        }
+                        Mode::Normal
+                    }
+                    (Mode::Normal, Key::Char('i')) => match selected {
+                        Some(pid) => {
+                            message = String::from("");
+                            Mode::IoClass(pid)
+                        }
+                        None => Mode::Normal,
+                    },
+                    (Mode::Normal, Key::Char('/')) => {
+                        message = String::from("");
+                        prev_search = (opt.keyword.clone(), View::search_logic(&opt, config));
+                        Mode::Search(opt.keyword.join(" "))
+                    }
+                    (Mode::Normal, _) => Mode::Normal,
+                    (Mode::Signal(pid), Key::Char(x)) if ('1'..='8').contains(&x) => {
+                        Mode::Confirm(x as usize - '1' as usize, pid)
+                    }
+                    (Mode::Signal(_), Key::Char('q')) | (Mode::Signal(_), Key::Escape) => {
+                        Mode::Normal
+                    }
+                    (Mode::Signal(pid), _) => Mode::Signal(pid),
+                    (Mode::Confirm(i, pid), Key::Char('y')) => {
+                        message = Watcher::with_target(&view, pid, || {
+                            Watcher::send_signal(&view, &opt, pid, i)
+                        });
+                        Mode::Normal
+                    }
+                    (Mode::Confirm(_, _), _) => Mode::Normal,
+                    (Mode::IoClass(pid), Key::Char(x)) if ('1'..='3').contains(&x) => {
+                        let class = x as usize - '1' as usize;
+                        // The idle class has no level
+                        if IO_CLASSES[class] == "idle" {
+                            message = Watcher::with_target(&view, pid, || {
+                                Watcher::set_ioprio(pid, class, 0)
+                            });
+                            Mode::Normal
+                        } else {
+                            Mode::IoLevel(class, pid)
+                        }
+                    }
+                    (Mode::IoClass(_), Key::Char('q')) | (Mode::IoClass(_), Key::Escape) => {
+                        Mode::Normal
+                    }
+                    (Mode::IoClass(pid), _) => Mode::IoClass(pid),
+                    (Mode::IoLevel(_, _), Key::Char('q')) | (Mode::IoLevel(_, _), Key::Escape) => {
+                        Mode::Normal
+                    }
+                    (Mode::IoLevel(i, pid), Key::Char(x)) if ('0'..='7').contains(&x) => {
+                        let level = x as usize - '0' as usize;
+                        message =
+                            Watcher::with_target(&view, pid, || Watcher::set_ioprio(pid, i, level));
+                        Mode::Normal
+                    }
+                    (Mode::IoLevel(i, pid), _) => Mode::IoLevel(i, pid),
+                    (Mode::Search(_), Key::Enter) => {
+                        message = String::from("");
+                        Mode::Normal
+                    }
+                    (Mode::Search(_), Key::Escape) => {
+                        opt.keyword = prev_search.0.clone();
+                        Watcher::set_logic(&mut opt, &prev_search.1);
+                        message = String::from("");
+                        Mode::Normal
+                    }
+                    (Mode::Search(x), Key::Tab) => {
+                        let logic = match View::search_logic(&opt, config) {
+                            ConfigSearchLogic::And => ConfigSearchLogic::Or,
+                            ConfigSearchLogic::Or => ConfigSearchLogic::Nand,
+                            ConfigSearchLogic::Nand => ConfigSearchLogic::Nor,
+                            ConfigSearchLogic::Nor => ConfigSearchLogic::And,
+                        };
+                        Watcher::set_logic(&mut opt, &logic);
+                        Mode::Search(x)
+                    }
+                    (Mode::Search(mut x), Key::Backspace) => {
+                        x.pop();
+                        message = Watcher::set_keyword(&mut opt, config, &x);
+                        Mode::Search(x)
+                    }
+                    (Mode::Search(mut x), Key::Char(c)) if !c.is_control() => {
+                        x.push(c);
+                        message = Watcher::set_keyword(&mut opt, config, &x);
+                        Mode::Search(x)
+                    }
+                    (Mode::Search(x), _) => Mode::Search(x),
+                };
+
+                // Columns required by the new field-qualified keywords are collected by View::new
+                if View::query_kinds(&opt) != query_kinds {
+                    refresh = true;
                 }
             }
 